@@ -1,55 +1,133 @@
 extern crate criterion;
 extern crate rust_salsa20;
 
-use rust_salsa20::{Salsa20, Key::Key16};
+use rust_salsa20::{Salsa20, Salsa20Key, Key::Key16};
 use criterion::*;
 
 fn encrypt_1_kb(c: &mut Criterion) {
     let mut salsa20 = Salsa20::new(Key16([1; 16]), [0; 8], 0);
     let mut buffer = [0; 1024];
 
-    c.bench(
-        "encrypt",
-        Benchmark::new(
-            "1Kb", move |b| b.iter(|| salsa20.encrypt(black_box(&mut buffer)))
-        ).throughput(Throughput::Bytes(1024))
-    );
+    c.benchmark_group("encrypt")
+        .throughput(Throughput::Bytes(1024))
+        .bench_function(
+            "1Kb", |b| b.iter(|| salsa20.encrypt(black_box(&mut buffer)))
+        );
 }
 
 fn generate_1_kb(c: &mut Criterion) {
     let mut salsa20 = Salsa20::new(Key16([2; 16]), [0; 8], 0);
     let mut buffer = [0; 1024];
 
-    c.bench(
-        "generate",
-        Benchmark::new(
-            "1Kb", move |b| b.iter(|| salsa20.generate(black_box(&mut buffer)))
-        ).throughput(Throughput::Bytes(1024))
-    );
+    c.benchmark_group("generate")
+        .throughput(Throughput::Bytes(1024))
+        .bench_function(
+            "1Kb", |b| b.iter(|| salsa20.generate(black_box(&mut buffer)))
+        );
 }
 
 fn generate_1_kb_with_overflow(c: &mut Criterion) {
     let mut salsa20 = Salsa20::new(Key16([3; 16]), [0; 8], 0);
     let mut buffer = [0; 1024];
 
-    c.bench(
-        "generate with overflow",
-        Benchmark::new(
+    c.benchmark_group("generate with overflow")
+        .throughput(Throughput::Bytes(1024))
+        .bench_function(
             "1Kb",
-            move |b| b.iter(|| {
+            |b| b.iter(|| {
                 salsa20.generate(black_box(&mut buffer[0..7]));
                 salsa20.generate(black_box(&mut buffer[7..259]));
                 salsa20.generate(black_box(&mut buffer[259..938]));
                 salsa20.generate(black_box(&mut buffer[938..1024]));
             })
-        ).throughput(Throughput::Bytes(1024))
-    );
+        );
+}
+
+const SIZES: [(&str, usize); 3] = [
+    ("1KiB", 1 << 10),
+    ("64KiB", 1 << 16),
+    ("1MiB", 1 << 20)
+];
+
+fn encrypt_sizes(c: &mut Criterion) {
+    let mut salsa20 = Salsa20::new(Key16([4; 16]), [0; 8], 0);
+    let mut group = c.benchmark_group("encrypt sizes");
+
+    for &(name, len) in &SIZES {
+        let mut buffer = vec![0; len];
+        group
+            .throughput(Throughput::Bytes(len as u64))
+            .bench_function(
+                name, |b| b.iter(|| salsa20.encrypt(black_box(&mut buffer)))
+            );
+    }
+}
+
+fn generate_sizes(c: &mut Criterion) {
+    let mut salsa20 = Salsa20::new(Key16([5; 16]), [0; 8], 0);
+    let mut group = c.benchmark_group("generate sizes");
+
+    for &(name, len) in &SIZES {
+        let mut buffer = vec![0; len];
+        group
+            .throughput(Throughput::Bytes(len as u64))
+            .bench_function(
+                name, |b| b.iter(|| salsa20.generate(black_box(&mut buffer)))
+            );
+    }
+}
+
+// lengths that leave whole blocks after the 512-byte and 256-byte paths,
+// to compare the builds with and without the `simd` feature
+const TAIL_SIZES: [(&str, usize); 3] = [
+    ("448B", 448),
+    ("960B", 960),
+    ("4032B", 4032)
+];
+
+fn generate_tails(c: &mut Criterion) {
+    let mut salsa20 = Salsa20::new(Key16([7; 16]), [0; 8], 0);
+    let mut group = c.benchmark_group("generate tails");
+
+    for &(name, len) in &TAIL_SIZES {
+        let mut buffer = vec![0; len];
+        group
+            .throughput(Throughput::Bytes(len as u64))
+            .bench_function(
+                name, |b| b.iter(|| salsa20.generate(black_box(&mut buffer)))
+            );
+    }
+}
+
+fn encrypt_messages(c: &mut Criterion) {
+    let key = Key16([6; 16]);
+    let salsa20_key = Salsa20Key::new(key);
+    let mut salsa20 = salsa20_key.stream([0; 8], 0);
+    let mut buffer = [0; 64];
+    let mut group = c.benchmark_group("encrypt 64B messages");
+    group.throughput(Throughput::Bytes(64));
+
+    group.bench_function("new", |b| b.iter(|| {
+        Salsa20::new(key, black_box([1; 8]), 0).encrypt(&mut buffer)
+    }));
+    group.bench_function("stream", |b| b.iter(|| {
+        salsa20_key.stream(black_box([1; 8]), 0).encrypt(&mut buffer)
+    }));
+    group.bench_function("set_nonce", |b| b.iter(|| {
+        salsa20.set_nonce(black_box([1; 8]));
+        salsa20.set_counter(0);
+        salsa20.encrypt(&mut buffer)
+    }));
 }
 
 criterion_group!(
     benches,
     encrypt_1_kb,
     generate_1_kb,
-    generate_1_kb_with_overflow
+    generate_1_kb_with_overflow,
+    encrypt_sizes,
+    generate_sizes,
+    generate_tails,
+    encrypt_messages
 );
 criterion_main!(benches);
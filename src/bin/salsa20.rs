@@ -0,0 +1,348 @@
+//! # salsa20
+//! Command-line tool that encrypts and decrypts files with the Salsa20
+//! family stream ciphers and writes their raw keystream
+
+#![deny(unsafe_code)]
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read, Write};
+use std::process;
+use rust_salsa20::{CounterOverflow, Key, Nonce, XSalsa20};
+use rust_salsa20::{Salsa20, Salsa20_12, Salsa20_8};
+
+const USAGE: &str = "\
+usage: salsa20 encrypt [options] [INPUT]
+       salsa20 decrypt [options] [INPUT]
+       salsa20 keystream --length LENGTH [options]
+
+Encrypts or decrypts INPUT, or the standard input if INPUT is missing or -,
+or writes LENGTH bytes of the keystream.
+
+options:
+    --key HEX           key, 32 or 64 hexadecimal digits; other users can
+                        read it in ps or /proc/PID/cmdline, --key-file
+                        keeps the key off the command line
+    --key-file PATH     file holding the 16 or 32 bytes of the key, or -
+                        to read them from the standard input, INPUT is
+                        then a file
+    --nonce HEX         nonce, 16 hexadecimal digits or 48 for xsalsa20;
+                        encrypt and keystream generate a random nonce and
+                        print it to the standard error when it is missing
+    --counter N         number of the first 64-byte block, 0 by default
+    --offset N          bytes of the sequence skipped after the start of
+                        the first block, 0 by default
+    --variant NAME      salsa20 (default), salsa20/12, salsa20/8 or xsalsa20
+    --length N          number of keystream bytes to write
+    -o, --output PATH   output file instead of the standard output
+    -h, --help          prints this message
+";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Encrypt,
+    Decrypt,
+    Keystream
+}
+
+#[derive(Clone, Copy)]
+enum Variant {
+    Salsa20,
+    Salsa20_12,
+    Salsa20_8,
+    XSalsa20
+}
+
+struct Options {
+    command: Command,
+    key: Option<Key>,
+    nonce: Option<String>,
+    counter: u64,
+    offset: u128,
+    variant: Variant,
+    length: Option<u64>,
+    input: Option<String>,
+    output: Option<String>
+}
+
+enum Cipher {
+    Salsa20(Salsa20),
+    Salsa20_12(Salsa20_12),
+    Salsa20_8(Salsa20_8),
+    XSalsa20(XSalsa20)
+}
+
+impl Cipher {
+    fn new(options: &Options) -> Result<Cipher, String> {
+        let key = options.key.ok_or("missing key, use --key or --key-file")?;
+        let counter = options.counter;
+        let mut cipher = match options.variant {
+            Variant::Salsa20 => {
+                Cipher::Salsa20(Salsa20::new(key, nonce(options)?, counter))
+            }
+            Variant::Salsa20_12 => Cipher::Salsa20_12(
+                Salsa20_12::new(key, nonce(options)?, counter)
+            ),
+            Variant::Salsa20_8 => Cipher::Salsa20_8(
+                Salsa20_8::new(key, nonce(options)?, counter)
+            ),
+            Variant::XSalsa20 => {
+                Cipher::XSalsa20(XSalsa20::new(key, nonce(options)?, counter))
+            }
+        };
+
+        if options.offset != 0 {
+            let position = u128::from(counter) * 64 + options.offset;
+            if position >= 1 << 70 {
+                return Err("offset past the end of the sequence".into());
+            }
+            cipher.seek(position);
+        }
+        Ok(cipher)
+    }
+
+    fn seek(&mut self, byte_pos: u128) {
+        match self {
+            Cipher::Salsa20(cipher) => cipher.seek(byte_pos),
+            Cipher::Salsa20_12(cipher) => cipher.seek(byte_pos),
+            Cipher::Salsa20_8(cipher) => cipher.seek(byte_pos),
+            Cipher::XSalsa20(cipher) => cipher.seek(byte_pos)
+        }
+    }
+
+    fn encrypt(&mut self, buffer: &mut [u8]) -> Result<(), CounterOverflow> {
+        match self {
+            Cipher::Salsa20(cipher) => cipher.try_encrypt(buffer),
+            Cipher::Salsa20_12(cipher) => cipher.try_encrypt(buffer),
+            Cipher::Salsa20_8(cipher) => cipher.try_encrypt(buffer),
+            Cipher::XSalsa20(cipher) => cipher.try_encrypt(buffer)
+        }
+    }
+
+    fn generate(&mut self, buffer: &mut [u8]) -> Result<(), CounterOverflow> {
+        match self {
+            Cipher::Salsa20(cipher) => cipher.try_generate(buffer),
+            Cipher::Salsa20_12(cipher) => cipher.try_generate(buffer),
+            Cipher::Salsa20_8(cipher) => cipher.try_generate(buffer),
+            Cipher::XSalsa20(cipher) => cipher.try_generate(buffer)
+        }
+    }
+}
+
+// parses the nonce of the options, or generates one for a new sequence
+fn nonce<const N: usize>(options: &Options) -> Result<[u8; N], String> {
+    if let Some(nonce) = &options.nonce {
+        return Nonce::<N>::from_hex(nonce)
+            .map(|nonce| nonce.0)
+            .map_err(|error| format!("invalid nonce: {}", error));
+    }
+    if options.command == Command::Decrypt {
+        return Err("missing nonce, use --nonce".into());
+    }
+
+    let mut nonce = Nonce([0; N]);
+    getrandom::getrandom(&mut nonce.0)
+        .map_err(|error| format!("cannot generate nonce: {}", error))?;
+    let mut buffer = [0; 48];
+    let hex = nonce.to_hex(&mut buffer).expect("48 digits at most");
+    eprintln!("nonce: {}", hex);
+    Ok(nonce.0)
+}
+
+fn parse<I>(mut args: I) -> Result<Option<Options>, String>
+    where I: Iterator<Item = String>
+{
+    let command = match args.next().as_deref() {
+        Some("encrypt") => Command::Encrypt,
+        Some("decrypt") => Command::Decrypt,
+        Some("keystream") => Command::Keystream,
+        Some("-h") | Some("--help") => return Ok(None),
+        Some(command) => return Err(format!("unknown command {}", command)),
+        None => return Err("missing command".into())
+    };
+
+    let mut options = Options {
+        command,
+        key: None,
+        nonce: None,
+        counter: 0,
+        offset: 0,
+        variant: Variant::Salsa20,
+        length: None,
+        input: None,
+        output: None
+    };
+
+    // `--key-file -` reads the key once the input is known
+    let mut stdin_key = false;
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().ok_or_else(|| format!("missing value of {}", arg))
+        };
+        let number = |value: String| {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("invalid value of {}: {}", arg, value))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--key" => {
+                let key = Key::from_hex(&value()?)
+                    .map_err(|error| format!("invalid key: {}", error))?;
+                options.key = Some(key);
+                stdin_key = false;
+            }
+            "--key-file" => {
+                let path = value()?;
+                stdin_key = path == "-";
+                if !stdin_key {
+                    let bytes = fs::read(&path)
+                        .map_err(|error| format!("{}: {}", path, error))?;
+                    options.key = Some(key_from_slice(&path, &bytes)?);
+                }
+            }
+            "--nonce" => options.nonce = Some(value()?),
+            "--counter" => options.counter = number(value()?)?,
+            "--offset" => options.offset = u128::from(number(value()?)?),
+            "--length" => options.length = Some(number(value()?)?),
+            "--variant" => {
+                options.variant = match value()?.as_str() {
+                    "salsa20" | "salsa20/20" => Variant::Salsa20,
+                    "salsa20/12" => Variant::Salsa20_12,
+                    "salsa20/8" => Variant::Salsa20_8,
+                    "xsalsa20" => Variant::XSalsa20,
+                    variant => {
+                        return Err(format!("unknown variant {}", variant));
+                    }
+                };
+            }
+            "-o" | "--output" => options.output = Some(value()?),
+            path if path == "-" || !path.starts_with('-') => {
+                if options.input.is_some() {
+                    return Err(format!("unexpected argument {}", path));
+                }
+                options.input = Some(arg.clone());
+            }
+            _ => return Err(format!("unknown option {}", arg))
+        }
+    }
+
+    match (options.command, &options.input, options.length) {
+        (Command::Keystream, Some(_), _) => {
+            return Err("keystream takes no input".into());
+        }
+        (Command::Keystream, _, None) => {
+            return Err("missing --length".into());
+        }
+        (Command::Keystream, _, _) | (_, _, None) => {}
+        _ => return Err("--length only applies to keystream".into())
+    }
+
+    if stdin_key {
+        let stdin_input = options.command != Command::Keystream
+            && matches!(options.input.as_deref(), None | Some("-"));
+        if stdin_input {
+            return Err("--key-file - takes the standard input, \
+                        INPUT must be a file".into());
+        }
+
+        let mut bytes = Vec::new();
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|error| format!("stdin: {}", error))?;
+        options.key = Some(key_from_slice("stdin", &bytes)?);
+    }
+    Ok(Some(options))
+}
+
+fn key_from_slice(name: &str, bytes: &[u8]) -> Result<Key, String> {
+    Key::from_slice(bytes).map_err(|error| format!("{}: {}", name, error))
+}
+
+// whether `output` names the file of the `input`, a missing output is not
+fn same_file(input: &str, output: &str) -> bool {
+    match (fs::canonicalize(input), fs::canonicalize(output)) {
+        (Ok(input), Ok(output)) => input == output,
+        _ => false
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let mut cipher = Cipher::new(options)?;
+
+    // the input is opened before the output is created, so that a failure
+    // leaves no output behind and the input is never truncated
+    let input = match options.input.as_deref() {
+        None | Some("-") => None,
+        Some(path) => {
+            let file = File::open(path)
+                .map_err(|error| format!("{}: {}", path, error))?;
+            Some((path, file))
+        }
+    };
+    if let (Some((input_path, _)), Some(output_path)) =
+        (&input, &options.output)
+    {
+        if same_file(input_path, output_path) {
+            return Err(format!("{}: output is the input", output_path));
+        }
+    }
+
+    let output_name = options.output.as_deref().unwrap_or("stdout");
+    let mut output: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(
+            File::create(path)
+                .map_err(|error| format!("{}: {}", path, error))?
+        ),
+        None => Box::new(io::stdout().lock())
+    };
+    let write_error = |error: io::Error| format!("{}: {}", output_name, error);
+    let overflow_error = |error: CounterOverflow| error.to_string();
+
+    let mut buffer = vec![0; 1 << 16];
+    if let Some(mut remaining) = options.length {
+        while remaining != 0 {
+            let len = remaining.min(buffer.len() as u64) as usize;
+            cipher.generate(&mut buffer[..len]).map_err(overflow_error)?;
+            output.write_all(&buffer[..len]).map_err(write_error)?;
+            remaining -= len as u64;
+        }
+        return output.flush().map_err(write_error);
+    }
+
+    let (input_name, mut input): (&str, Box<dyn Read>) = match input {
+        Some((path, file)) => (path, Box::new(file)),
+        None => ("stdin", Box::new(io::stdin().lock()))
+    };
+    loop {
+        let len = match input.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(format!("{}: {}", input_name, error))
+        };
+        cipher.encrypt(&mut buffer[..len]).map_err(overflow_error)?;
+        output.write_all(&buffer[..len]).map_err(write_error)?;
+    }
+    output.flush().map_err(write_error)
+}
+
+fn main() {
+    let options = match parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("salsa20: {}\ntry salsa20 --help", message);
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = run(&options) {
+        eprintln!("salsa20: {}", message);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,355 @@
+use core::fmt;
+use crate::{Key, Overflow};
+use crate::utils::{u8_to_u32, xor_from_slice};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+#[cfg(feature = "zeroize")]
+use crate::secret::Wipe;
+
+fn quarterround(a: u32, b: u32, c: u32, d: u32) -> [u32; 4] {
+    let a = a.wrapping_add(b);
+    let d = (d ^ a).rotate_left(16);
+    let c = c.wrapping_add(d);
+    let b = (b ^ c).rotate_left(12);
+    let a = a.wrapping_add(b);
+    let d = (d ^ a).rotate_left(8);
+    let c = c.wrapping_add(d);
+    let b = (b ^ c).rotate_left(7);
+
+    [a, b, c, d]
+}
+
+fn columnround(y: [u32; 16]) -> [u32; 16] {
+    let [
+        [z0, z4, z8, z12],
+        [z1, z5, z9, z13],
+        [z2, z6, z10, z14],
+        [z3, z7, z11, z15]
+    ] = [
+        quarterround(y[0], y[4], y[8], y[12]),
+        quarterround(y[1], y[5], y[9], y[13]),
+        quarterround(y[2], y[6], y[10], y[14]),
+        quarterround(y[3], y[7], y[11], y[15])
+    ];
+
+    [z0, z1, z2, z3, z4, z5, z6, z7, z8, z9, z10, z11, z12, z13, z14, z15]
+}
+
+fn diagonalround(y: [u32; 16]) -> [u32; 16] {
+    let [
+        [z0, z5, z10, z15],
+        [z1, z6, z11, z12],
+        [z2, z7, z8, z13],
+        [z3, z4, z9, z14]
+    ] = [
+        quarterround(y[0], y[5], y[10], y[15]),
+        quarterround(y[1], y[6], y[11], y[12]),
+        quarterround(y[2], y[7], y[8], y[13]),
+        quarterround(y[3], y[4], y[9], y[14])
+    ];
+
+    [z0, z1, z2, z3, z4, z5, z6, z7, z8, z9, z10, z11, z12, z13, z14, z15]
+}
+
+fn doubleround(y: [u32; 16]) -> [u32; 16] {
+    diagonalround(columnround(y))
+}
+
+#[derive(Clone, Copy)]
+struct Generator {
+    init_matrix: [u32; 16]
+}
+
+// the matrix holds the key, so only the counter words are shown
+impl fmt::Debug for Generator {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Generator")
+            .field("counter", &&self.init_matrix[12..14])
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for Generator {
+    fn zeroize(&mut self) {
+        self.init_matrix.zeroize();
+    }
+}
+
+impl Generator {
+    fn new(key: Key, input: &[u8; 16]) -> Generator {
+        let mut init_matrix = [0; 16];
+        init_matrix[0] = 1634760805;
+        init_matrix[3] = 1797285236;
+        u8_to_u32(&input[..], &mut init_matrix[12..16]);
+
+        match key {
+            Key::Key16(key) => {
+                u8_to_u32(&key[..], &mut init_matrix[4..8]);
+                u8_to_u32(&key[..], &mut init_matrix[8..12]);
+                init_matrix[1] = 824206446;
+                init_matrix[2] = 2036477238;
+            }
+            Key::Key32(key) => {
+                u8_to_u32(&key[..], &mut init_matrix[4..12]);
+                init_matrix[1] = 857760878;
+                init_matrix[2] = 2036477234;
+            }
+        }
+
+        Generator { init_matrix }
+    }
+
+    fn doublerounds(&self) -> [u32; 16] {
+        (0..10).fold(self.init_matrix, |block, _| doubleround(block))
+    }
+
+    fn next(&self) -> [u8; 64] {
+        let mut buffer = [0; 64];
+        self.doublerounds()
+            .iter()
+            .zip(self.init_matrix.iter())
+            .enumerate()
+            .for_each(|(index, (drounds_value, &init_value))| {
+                let offset = index * 4;
+                let sum = drounds_value.wrapping_add(init_value);
+                buffer[offset..offset + 4].copy_from_slice(&sum.to_le_bytes());
+            });
+
+        buffer
+    }
+}
+
+/// derives a 32-byte subkey from the `key` and a 16-byte `input` with
+/// HChaCha20
+/// # Arguments
+/// * `key` - secret key, 32-byte or 16-byte sequence
+/// * `input` - 16-byte sequence, placed where ChaCha20 keeps nonce and counter
+pub fn hchacha20(key: &Key, input: &[u8; 16]) -> [u8; 32] {
+    let block = Generator::new(*key, input).doublerounds();
+
+    let mut buffer = [0; 32];
+    [0, 1, 2, 3, 12, 13, 14, 15]
+        .iter()
+        .enumerate()
+        .for_each(|(index, &position)| {
+            let offset = index * 4;
+            buffer[offset..offset + 4]
+                .copy_from_slice(&block[position].to_le_bytes());
+        });
+
+    buffer
+}
+
+/// The ChaCha20 stream cipher with a 8-byte nonce and a 64-bit counter
+#[derive(Clone, Copy, Debug)]
+pub struct ChaCha20 {
+    generator: Generator,
+    overflow: Overflow
+}
+
+#[cfg(feature = "zeroize")]
+impl Wipe for ChaCha20 {
+    fn wipe(&mut self) {
+        self.generator.zeroize();
+        self.overflow.zeroize();
+    }
+}
+
+impl ChaCha20 {
+    /// creates ChaCha20 stream cipher
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 8-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new(key: Key, nonce: [u8; 8], counter: u64) -> ChaCha20 {
+        let mut input = [0; 16];
+        input[..8].copy_from_slice(&counter.to_le_bytes());
+        input[8..].copy_from_slice(&nonce);
+
+        let overflow = Overflow::new([0; 64], 64);
+        let generator = Generator::new(key, &input);
+        ChaCha20 { generator, overflow }
+    }
+
+    fn counter(&self) -> u64 {
+        let [low, high] = [
+            self.generator.init_matrix[12],
+            self.generator.init_matrix[13]
+        ];
+        u64::from(low) | u64::from(high) << 32
+    }
+
+    fn modify<F>(&mut self, buffer: &mut [u8], modifier: &F)
+        where F: Fn(&mut [u8], &[u8])
+    {
+        let mut counter = self.counter();
+        let generator = &mut self.generator;
+        self.overflow.apply(buffer, modifier, |keystream| {
+            for block in keystream.chunks_exact_mut(64) {
+                block.copy_from_slice(&generator.next());
+                counter = counter.wrapping_add(1);
+                generator.init_matrix[12] = counter as u32;
+                generator.init_matrix[13] = (counter >> 32) as u32;
+            }
+        });
+    }
+
+    /// sets unique number of next 64-byte block
+    pub fn set_counter(&mut self, counter: u64) {
+        self.generator.init_matrix[12] = counter as u32;
+        self.generator.init_matrix[13] = (counter >> 32) as u32;
+        self.overflow = Overflow::new([0; 64], 64);
+    }
+
+    /// generates sequence to `buffer` with `nonce` under the `key`
+    pub fn generate(&mut self, buffer: &mut [u8]) {
+        self.modify(buffer, &<[u8]>::copy_from_slice);
+    }
+
+    /// encrypts a `buffer` with `nonce` under the `key`
+    pub fn encrypt(&mut self, buffer: &mut [u8]) {
+        self.modify(buffer, &xor_from_slice);
+    }
+}
+
+/// The ChaCha20 stream cipher with a 12-byte nonce and a 32-bit counter,
+/// as specified in RFC 8439
+#[derive(Clone, Copy, Debug)]
+pub struct ChaCha20Ietf {
+    generator: Generator,
+    overflow: Overflow
+}
+
+#[cfg(feature = "zeroize")]
+impl Wipe for ChaCha20Ietf {
+    fn wipe(&mut self) {
+        self.generator.zeroize();
+        self.overflow.zeroize();
+    }
+}
+
+impl ChaCha20Ietf {
+    /// creates ChaCha20 stream cipher
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 12-byte unique sequence
+    /// * `counter` - 4-byte unique number of each 64-byte block
+    pub fn new(key: Key, nonce: [u8; 12], counter: u32) -> ChaCha20Ietf {
+        let mut input = [0; 16];
+        input[..4].copy_from_slice(&counter.to_le_bytes());
+        input[4..].copy_from_slice(&nonce);
+
+        let overflow = Overflow::new([0; 64], 64);
+        let generator = Generator::new(key, &input);
+        ChaCha20Ietf { generator, overflow }
+    }
+
+    fn modify<F>(&mut self, buffer: &mut [u8], modifier: &F)
+        where F: Fn(&mut [u8], &[u8])
+    {
+        let generator = &mut self.generator;
+        self.overflow.apply(buffer, modifier, |keystream| {
+            for block in keystream.chunks_exact_mut(64) {
+                block.copy_from_slice(&generator.next());
+                generator.init_matrix[12] =
+                    generator.init_matrix[12].wrapping_add(1);
+            }
+        });
+    }
+
+    /// sets unique number of next 64-byte block
+    pub fn set_counter(&mut self, counter: u32) {
+        self.generator.init_matrix[12] = counter;
+        self.overflow = Overflow::new([0; 64], 64);
+    }
+
+    /// generates sequence to `buffer` with `nonce` under the `key`
+    pub fn generate(&mut self, buffer: &mut [u8]) {
+        self.modify(buffer, &<[u8]>::copy_from_slice);
+    }
+
+    /// encrypts a `buffer` with `nonce` under the `key`
+    pub fn encrypt(&mut self, buffer: &mut [u8]) {
+        self.modify(buffer, &xor_from_slice);
+    }
+}
+
+/// The XChaCha20 stream cipher, ChaCha20 with a 24-byte nonce
+#[derive(Clone, Copy, Debug)]
+pub struct XChaCha20 {
+    chacha20: ChaCha20
+}
+
+#[cfg(feature = "zeroize")]
+impl Wipe for XChaCha20 {
+    fn wipe(&mut self) {
+        self.chacha20.wipe();
+    }
+}
+
+impl XChaCha20 {
+    /// creates XChaCha20 stream cipher
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 24-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new(key: Key, nonce: [u8; 24], counter: u64) -> XChaCha20 {
+        let mut input = [0; 16];
+        let mut chacha20_nonce = [0; 8];
+        input.copy_from_slice(&nonce[..16]);
+        chacha20_nonce.copy_from_slice(&nonce[16..]);
+
+        let subkey = Key::Key32(hchacha20(&key, &input));
+        let chacha20 = ChaCha20::new(subkey, chacha20_nonce, counter);
+        XChaCha20 { chacha20 }
+    }
+
+    /// sets unique number of next 64-byte block
+    pub fn set_counter(&mut self, counter: u64) {
+        self.chacha20.set_counter(counter);
+    }
+
+    /// generates sequence to `buffer` with `nonce` under the `key`
+    pub fn generate(&mut self, buffer: &mut [u8]) {
+        self.chacha20.generate(buffer);
+    }
+
+    /// encrypts a `buffer` with `nonce` under the `key`
+    pub fn encrypt(&mut self, buffer: &mut [u8]) {
+        self.chacha20.encrypt(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarterround_test() {
+        assert_eq!(
+            quarterround(0x11111111, 0x01020304, 0x9b8d6f43, 0x01234567),
+            [0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb]
+        );
+    }
+
+    #[test]
+    fn doublerounds_test() {
+        let generator = Generator::new(Key::Key32([
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+            0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15,
+            0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f
+        ]), &[
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00,
+            0x4a, 0x00, 0x00, 0x00, 0x00
+        ]);
+
+        assert_eq!(generator.doublerounds(), [
+            0x837778ab, 0xe238d763, 0xa67ae21e, 0x5950bb2f,
+            0xc4f2d0c7, 0xfc62bb2f, 0x8fa018fc, 0x3f5ec7b7,
+            0x335271c2, 0xf29489f3, 0xeabda8fc, 0x82e46ebd,
+            0xd19c12b4, 0xb04e16de, 0x9e83d0cb, 0x4e3c50a2
+        ]);
+    }
+}
@@ -0,0 +1,411 @@
+//! # Container
+//! Self-describing encrypted files, sealed and opened as streams in chunks
+//! with Salsa20 or XSalsa20 and Poly1305
+//!
+//! ## Format
+//! All numbers are little-endian.
+//!
+//! | Field      | Length   | Value                                        |
+//! |------------|----------|----------------------------------------------|
+//! | magic      | 8        | `SALSA20F`                                   |
+//! | version    | 1        | 1                                            |
+//! | variant    | 1        | 1 for Salsa20, 2 for XSalsa20                |
+//! | chunk size | 4        | plaintext bytes of each full chunk           |
+//! | nonce      | 8 or 24  | nonce of the variant                         |
+//! | tag        | 16       | Poly1305 tag of the fields above             |
+//! | chunks     | any      | each encrypted chunk and its Poly1305 tag    |
+//!
+//! The sequence of the key and nonce is split into ranges of whole 64-byte
+//! blocks. Block 0 gives the Poly1305 key of the header. The chunk `i`
+//! takes the next `1 + ceil(chunk size / 64)` blocks from block
+//! `1 + i * (1 + ceil(chunk size / 64))`: the first gives its Poly1305 key
+//! and the others encrypt it. So a chunk only verifies at its own place.
+//!
+//! Every chunk but the last holds exactly `chunk size` bytes and the last
+//! one holds fewer, possibly none, so a file cut at the end of a chunk
+//! lacks its last chunk and does not open.
+//!
+//! ## Example
+//! ```
+//! extern crate rust_salsa20;
+//! use rust_salsa20::Key::Key32;
+//! use rust_salsa20::container::{open_file, seal_file, Header, Variant};
+//!
+//! fn main() {
+//!     let key = Key32([1; 32]);
+//!     let header = Header::new(Variant::XSalsa20([2; 24]));
+//!
+//!     let mut sealed = Vec::new();
+//!     seal_file(&key, &header, &b"attack at dawn"[..], &mut sealed).unwrap();
+//!
+//!     let mut opened = Vec::new();
+//!     assert_eq!(open_file(&key, &sealed[..], &mut opened).unwrap(), header);
+//!     assert_eq!(opened, b"attack at dawn");
+//! }
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::vec;
+use crate::{Key, Salsa20, XSalsa20};
+use crate::poly1305::{Poly1305, TAG_LEN};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Magic bytes at the start of a container
+pub const MAGIC: [u8; 8] = *b"SALSA20F";
+
+/// Version of the format written by `seal_file`
+pub const VERSION: u8 = 1;
+
+/// Chunk size of `Header::new`, 64 KiB
+pub const DEFAULT_CHUNK_SIZE: u32 = 1 << 16;
+
+/// Largest chunk size, 16 MiB
+pub const MAX_CHUNK_SIZE: u32 = 1 << 24;
+
+// magic, version, variant and chunk size
+const FIXED_HEADER_LEN: usize = 14;
+
+/// Cipher of a container with its nonce
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Salsa20([u8; 8]),
+    XSalsa20([u8; 24])
+}
+
+/// Header of a container
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// cipher and nonce, the nonce must be unique for each file sealed
+    /// under a key
+    pub variant: Variant,
+    /// plaintext bytes of each full chunk, from 1 to `MAX_CHUNK_SIZE`
+    pub chunk_size: u32
+}
+
+impl Header {
+    /// creates a header with the `DEFAULT_CHUNK_SIZE`
+    /// # Arguments
+    /// * `variant` - cipher and nonce
+    pub fn new(variant: Variant) -> Header {
+        Header { variant, chunk_size: DEFAULT_CHUNK_SIZE }
+    }
+
+    fn check_chunk_size(chunk_size: u32) -> Result<(), ContainerError> {
+        match chunk_size {
+            1..=MAX_CHUNK_SIZE => Ok(()),
+            _ => Err(ContainerError::InvalidHeader)
+        }
+    }
+}
+
+/// Error returned by `seal_file` and `open_file`
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ContainerError {
+    /// reading the input or writing the output failed
+    Io(io::Error),
+    /// the input is not a container, or its header has invalid fields
+    InvalidHeader,
+    /// the container has a version this crate does not read
+    UnsupportedVersion(u8),
+    /// the header or a chunk does not match its tag, the container was
+    /// tampered with or the key is wrong
+    AuthenticationFailed,
+    /// the container ends before its last chunk
+    Truncated,
+    /// the file is too long for the sequence of the key and nonce
+    CounterOverflow
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerError::Io(error) => error.fmt(formatter),
+            ContainerError::InvalidHeader => {
+                formatter.write_str("invalid container header")
+            }
+            ContainerError::UnsupportedVersion(version) => {
+                write!(formatter, "unsupported container version {}", version)
+            }
+            ContainerError::AuthenticationFailed => {
+                formatter.write_str("container authentication failed")
+            }
+            ContainerError::Truncated => {
+                formatter.write_str("truncated container")
+            }
+            ContainerError::CounterOverflow => {
+                formatter.write_str("salsa20 counter overflow")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContainerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ContainerError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for ContainerError {
+    fn from(error: io::Error) -> ContainerError {
+        ContainerError::Io(error)
+    }
+}
+
+enum Stream {
+    Salsa20(Salsa20),
+    XSalsa20(XSalsa20)
+}
+
+// the cipher of a container and the layout of its sequence
+struct Chunks {
+    stream: Stream,
+    chunk_blocks: u64
+}
+
+impl Chunks {
+    fn new(key: &Key, header: &Header) -> Chunks {
+        let stream = match header.variant {
+            Variant::Salsa20(nonce) => {
+                Stream::Salsa20(Salsa20::new(*key, nonce, 0))
+            }
+            Variant::XSalsa20(nonce) => {
+                Stream::XSalsa20(XSalsa20::new(*key, nonce, 0))
+            }
+        };
+        let chunk_blocks = 1 + u64::from(header.chunk_size).div_ceil(64);
+        Chunks { stream, chunk_blocks }
+    }
+
+    // moves to the block `counter` and takes a Poly1305 key from it
+    fn poly1305(&mut self, counter: u64) -> Poly1305 {
+        let mut poly1305_key = [0; 32];
+        match &mut self.stream {
+            Stream::Salsa20(salsa20) => {
+                salsa20.set_counter(counter);
+                salsa20.generate(&mut poly1305_key);
+            }
+            Stream::XSalsa20(xsalsa20) => {
+                xsalsa20.set_counter(counter);
+                xsalsa20.generate(&mut poly1305_key);
+            }
+        }
+        let poly1305 = Poly1305::new(&poly1305_key);
+        #[cfg(feature = "zeroize")]
+        poly1305_key.zeroize();
+        poly1305
+    }
+
+    fn header_poly1305(&mut self) -> Poly1305 {
+        self.poly1305(0)
+    }
+
+    // returns the Poly1305 of the chunk `index` and leaves the cipher at the
+    // first block of the chunk
+    fn chunk_poly1305(&mut self, index: u64)
+        -> Result<Poly1305, ContainerError>
+    {
+        let counter = index
+            .checked_mul(self.chunk_blocks)
+            .and_then(|blocks| blocks.checked_add(1))
+            .filter(|&counter| counter <= u64::MAX - self.chunk_blocks + 1)
+            .ok_or(ContainerError::CounterOverflow)?;
+        let poly1305 = self.poly1305(counter);
+        self.set_counter(counter + 1);
+        Ok(poly1305)
+    }
+
+    fn set_counter(&mut self, counter: u64) {
+        match &mut self.stream {
+            Stream::Salsa20(salsa20) => salsa20.set_counter(counter),
+            Stream::XSalsa20(xsalsa20) => xsalsa20.set_counter(counter)
+        }
+    }
+
+    fn encrypt(&mut self, buffer: &mut [u8]) {
+        match &mut self.stream {
+            Stream::Salsa20(salsa20) => salsa20.encrypt(buffer),
+            Stream::XSalsa20(xsalsa20) => xsalsa20.encrypt(buffer)
+        }
+    }
+}
+
+fn header_bytes(header: &Header) -> ([u8; FIXED_HEADER_LEN + 24], usize) {
+    let (variant, nonce): (u8, &[u8]) = match &header.variant {
+        Variant::Salsa20(nonce) => (1, nonce),
+        Variant::XSalsa20(nonce) => (2, nonce)
+    };
+
+    let mut bytes = [0; FIXED_HEADER_LEN + 24];
+    bytes[..8].copy_from_slice(&MAGIC);
+    bytes[8] = VERSION;
+    bytes[9] = variant;
+    bytes[10..14].copy_from_slice(&header.chunk_size.to_le_bytes());
+    bytes[14..14 + nonce.len()].copy_from_slice(nonce);
+    (bytes, FIXED_HEADER_LEN + nonce.len())
+}
+
+// reads until the `buffer` is full or the input ends and returns the number
+// of bytes read
+fn read_full<R: Read>(input: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len != buffer.len() {
+        match input.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error)
+        }
+    }
+    Ok(len)
+}
+
+fn read_exact<R: Read>(input: &mut R, buffer: &mut [u8])
+    -> Result<(), ContainerError>
+{
+    if read_full(input, buffer)? != buffer.len() {
+        return Err(ContainerError::Truncated);
+    }
+    Ok(())
+}
+
+/// encrypts the `input` to a container written to the `output` and returns
+/// the number of plaintext bytes
+/// # Arguments
+/// * `key` - secret key, 32-byte or 16-byte sequence
+/// * `header` - cipher, nonce and chunk size of the container
+/// * `input` - reader of the plaintext
+/// * `output` - writer of the container
+pub fn seal_file<R: Read, W: Write>(
+    key: &Key,
+    header: &Header,
+    mut input: R,
+    mut output: W
+) -> Result<u64, ContainerError> {
+    Header::check_chunk_size(header.chunk_size)?;
+    let mut chunks = Chunks::new(key, header);
+
+    let (header_bytes, header_len) = header_bytes(header);
+    let mut poly1305 = chunks.header_poly1305();
+    poly1305.update(&header_bytes[..header_len]);
+    output.write_all(&header_bytes[..header_len])?;
+    output.write_all(&poly1305.finalize())?;
+
+    let chunk_size = header.chunk_size as usize;
+    let mut buffer = vec![0; chunk_size + TAG_LEN];
+    let mut total = 0;
+    for index in 0.. {
+        let len = read_full(&mut input, &mut buffer[..chunk_size])?;
+        let mut poly1305 = chunks.chunk_poly1305(index)?;
+        chunks.encrypt(&mut buffer[..len]);
+        poly1305.update(&buffer[..len]);
+        buffer[len..len + TAG_LEN].copy_from_slice(&poly1305.finalize());
+        output.write_all(&buffer[..len + TAG_LEN])?;
+
+        total += len as u64;
+        if len != chunk_size {
+            break;
+        }
+    }
+
+    output.flush()?;
+    Ok(total)
+}
+
+// reads the header of a container from the `input`, verifies it and
+// returns it together with the cipher of the chunks
+fn open_header<R: Read>(key: &Key, input: &mut R)
+    -> Result<(Header, Chunks), ContainerError>
+{
+    let mut bytes = [0; FIXED_HEADER_LEN + 24];
+    read_exact(input, &mut bytes[..FIXED_HEADER_LEN])?;
+    if bytes[..8] != MAGIC {
+        return Err(ContainerError::InvalidHeader);
+    }
+    if bytes[8] != VERSION {
+        return Err(ContainerError::UnsupportedVersion(bytes[8]));
+    }
+
+    let nonce_len = match bytes[9] {
+        1 => 8,
+        2 => 24,
+        _ => return Err(ContainerError::InvalidHeader)
+    };
+    let mut chunk_size = [0; 4];
+    chunk_size.copy_from_slice(&bytes[10..14]);
+    let chunk_size = u32::from_le_bytes(chunk_size);
+    Header::check_chunk_size(chunk_size)?;
+
+    let header_len = FIXED_HEADER_LEN + nonce_len;
+    read_exact(input, &mut bytes[FIXED_HEADER_LEN..header_len])?;
+    let nonce = &bytes[FIXED_HEADER_LEN..header_len];
+    let variant = match nonce_len {
+        8 => Variant::Salsa20(<[u8; 8]>::try_from(nonce).expect("8 bytes")),
+        _ => {
+            Variant::XSalsa20(<[u8; 24]>::try_from(nonce).expect("24 bytes"))
+        }
+    };
+    let header = Header { variant, chunk_size };
+
+    let mut tag = [0; TAG_LEN];
+    read_exact(input, &mut tag)?;
+    let mut chunks = Chunks::new(key, &header);
+    let mut poly1305 = chunks.header_poly1305();
+    poly1305.update(&bytes[..header_len]);
+    poly1305
+        .verify(&tag)
+        .map_err(|_| ContainerError::AuthenticationFailed)?;
+
+    Ok((header, chunks))
+}
+
+/// verifies and decrypts a container read from the `input`, writes the
+/// plaintext to the `output` and returns the header
+///
+/// Each chunk is written once its tag is verified, so on failure the
+/// `output` may hold the verified chunks before the failing one, which
+/// must be discarded
+/// # Arguments
+/// * `key` - secret key, 32-byte or 16-byte sequence
+/// * `input` - reader of the container
+/// * `output` - writer of the plaintext
+pub fn open_file<R: Read, W: Write>(
+    key: &Key,
+    mut input: R,
+    mut output: W
+) -> Result<Header, ContainerError> {
+    let (header, mut chunks) = open_header(key, &mut input)?;
+
+    let chunk_size = header.chunk_size as usize;
+    let mut buffer = vec![0; chunk_size + TAG_LEN];
+    for index in 0.. {
+        let len = read_full(&mut input, &mut buffer)?;
+        if len < TAG_LEN {
+            return Err(ContainerError::Truncated);
+        }
+
+        let (chunk, tag) = buffer[..len].split_at_mut(len - TAG_LEN);
+        let mut chunk_tag = [0; TAG_LEN];
+        chunk_tag.copy_from_slice(tag);
+        let mut poly1305 = chunks.chunk_poly1305(index)?;
+        poly1305.update(chunk);
+        poly1305
+            .verify(&chunk_tag)
+            .map_err(|_| ContainerError::AuthenticationFailed)?;
+
+        chunks.encrypt(chunk);
+        output.write_all(chunk)?;
+        if chunk.len() != chunk_size {
+            break;
+        }
+    }
+
+    output.flush()?;
+    Ok(header)
+}
@@ -0,0 +1,219 @@
+//! Hexadecimal and Base64 (RFC 4648, standard alphabet with padding) forms
+//! of byte sequences, written to and read from the buffers of the caller
+
+use core::str;
+use crate::Error;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn hex_value(digit: u8) -> Result<u8, Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Error::InvalidEncoding)
+    }
+}
+
+fn base64_value(symbol: u8) -> Result<u32, Error> {
+    let value = match symbol {
+        b'A'..=b'Z' => symbol - b'A',
+        b'a'..=b'z' => symbol - b'a' + 26,
+        b'0'..=b'9' => symbol - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return Err(Error::InvalidEncoding)
+    };
+    Ok(u32::from(value))
+}
+
+/// writes the lowercase hexadecimal form of `input` to the start of
+/// `output`, which needs twice the length of `input`
+pub(super) fn encode_hex<'a>(input: &[u8], output: &'a mut [u8])
+    -> Result<&'a str, Error>
+{
+    let len = input.len() * 2;
+    if output.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    for (&byte, digits) in input.iter().zip(output.chunks_exact_mut(2)) {
+        digits[0] = HEX_DIGITS[usize::from(byte >> 4)];
+        digits[1] = HEX_DIGITS[usize::from(byte & 0xf)];
+    }
+    Ok(str::from_utf8(&output[..len]).expect("ascii digits"))
+}
+
+/// reads the hexadecimal form `input`, in upper or lower case, to the start
+/// of `output` and returns the number of bytes written
+pub(super) fn decode_hex(input: &str, output: &mut [u8])
+    -> Result<usize, Error>
+{
+    let input = input.as_bytes();
+    if !input.len().is_multiple_of(2) {
+        return Err(Error::InvalidEncoding);
+    }
+
+    let len = input.len() / 2;
+    if output.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    for (digits, byte) in input.chunks_exact(2).zip(output.iter_mut()) {
+        *byte = hex_value(digits[0])? << 4 | hex_value(digits[1])?;
+    }
+    Ok(len)
+}
+
+/// writes the padded Base64 form of `input` to the start of `output`, which
+/// needs 4 bytes for each started 3 bytes of `input`
+pub(super) fn encode_base64<'a>(input: &[u8], output: &'a mut [u8])
+    -> Result<&'a str, Error>
+{
+    let len = input.len().div_ceil(3) * 4;
+    if output.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    for (chunk, symbols) in input.chunks(3).zip(output.chunks_exact_mut(4)) {
+        let mut bytes = [0; 4];
+        bytes[1..=chunk.len()].copy_from_slice(chunk);
+        let value = u32::from_be_bytes(bytes);
+
+        for (index, symbol) in symbols.iter_mut().enumerate() {
+            *symbol = if index <= chunk.len() {
+                BASE64_ALPHABET[(value >> (18 - 6 * index) & 0x3f) as usize]
+            } else {
+                b'='
+            };
+        }
+    }
+    Ok(str::from_utf8(&output[..len]).expect("ascii symbols"))
+}
+
+/// reads the padded Base64 form `input` to the start of `output` and returns
+/// the number of bytes written, the unused bits of the last symbol must be
+/// zero so that each sequence has a single form
+pub(super) fn decode_base64(input: &str, output: &mut [u8])
+    -> Result<usize, Error>
+{
+    let input = input.as_bytes();
+    if !input.len().is_multiple_of(4) {
+        return Err(Error::InvalidEncoding);
+    }
+
+    let padding = input
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&symbol| symbol == b'=')
+        .count();
+    let len = input.len() / 4 * 3 - padding;
+    if output.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let chunks = input.chunks_exact(4);
+    let last_chunk = chunks.len().saturating_sub(1);
+    for (index, chunk) in chunks.enumerate() {
+        let symbols = if index == last_chunk { 4 - padding } else { 4 };
+        let value = chunk[..symbols]
+            .iter()
+            .try_fold(0, |value, &symbol| {
+                base64_value(symbol).map(|sextet| value << 6 | sextet)
+            })?
+            << (6 * (4 - symbols));
+
+        let bytes = value.to_be_bytes();
+        let chunk_len = symbols - 1;
+        if bytes[1 + chunk_len..].iter().any(|&byte| byte != 0) {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let offset = index * 3;
+        output[offset..offset + chunk_len]
+            .copy_from_slice(&bytes[1..=chunk_len]);
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test vectors of RFC 4648, section 10
+    const VECTORS: [(&str, &str, &str); 7] = [
+        ("", "", ""),
+        ("f", "66", "Zg=="),
+        ("fo", "666f", "Zm8="),
+        ("foo", "666f6f", "Zm9v"),
+        ("foob", "666f6f62", "Zm9vYg=="),
+        ("fooba", "666f6f6261", "Zm9vYmE="),
+        ("foobar", "666f6f626172", "Zm9vYmFy")
+    ];
+
+    #[test]
+    fn hex_test() {
+        for (data, hex, _) in VECTORS.iter() {
+            let mut buffer = [0; 12];
+            assert_eq!(encode_hex(data.as_bytes(), &mut buffer), Ok(*hex));
+
+            let mut buffer = [0; 6];
+            let len = decode_hex(hex, &mut buffer).unwrap();
+            assert_eq!(&buffer[..len], data.as_bytes());
+        }
+
+        let mut buffer = [0; 4];
+        assert_eq!(decode_hex("0aFf", &mut buffer), Ok(2));
+        assert_eq!(buffer[..2], [0x0a, 0xff]);
+        assert_eq!(decode_hex("abc", &mut buffer), Err(Error::InvalidEncoding));
+        assert_eq!(decode_hex("0g", &mut buffer), Err(Error::InvalidEncoding));
+        assert_eq!(decode_hex("+1", &mut buffer), Err(Error::InvalidEncoding));
+        assert_eq!(
+            decode_hex("0102030405", &mut buffer),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(
+            encode_hex(&[1; 3], &mut buffer),
+            Err(Error::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn base64_test() {
+        for (data, _, base64) in VECTORS.iter() {
+            let mut buffer = [0; 8];
+            let encoded = encode_base64(data.as_bytes(), &mut buffer);
+            assert_eq!(encoded, Ok(*base64));
+
+            let mut buffer = [0; 6];
+            let len = decode_base64(base64, &mut buffer).unwrap();
+            assert_eq!(&buffer[..len], data.as_bytes());
+        }
+
+        let mut buffer = [0; 3];
+        assert_eq!(decode_base64("+/+/", &mut buffer), Ok(3));
+        assert_eq!(buffer, [0xfb, 0xff, 0xbf]);
+
+        let invalid_data = [
+            "Zg", "Zg=", "Zh==", "Zm9=", "Z===", "====", "Zg==Zg==", "Zm9-",
+            "Zm 9"
+        ];
+        for invalid in invalid_data.iter() {
+            let mut buffer = [0; 6];
+            let result = decode_base64(invalid, &mut buffer);
+            assert_eq!(result, Err(Error::InvalidEncoding), "{}", invalid);
+        }
+
+        assert_eq!(
+            decode_base64("Zm9vYg==", &mut buffer),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(
+            encode_base64(b"foo", &mut buffer),
+            Err(Error::BufferTooSmall)
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! # io
+//! `std::io` adapters that encrypt bytes written to a `Write` and decrypt
+//! bytes read from a `Read` with Salsa20
+//!
+//! ## Example
+//! ```
+//! extern crate rust_salsa20;
+//! use std::io::{Read, Write};
+//! use rust_salsa20::{Salsa20, Key::Key32};
+//! use rust_salsa20::io::{DecryptingReader, EncryptingWriter};
+//!
+//! fn main() {
+//!     let key = Key32([1; 32]);
+//!     let nonce = [2; 8];
+//!
+//!     let mut writer = EncryptingWriter::new(
+//!         Salsa20::new(key, nonce, 0),
+//!         Vec::new()
+//!     );
+//!     writer.write_all(b"attack at dawn").unwrap();
+//!     let ciphertext = writer.into_inner();
+//!
+//!     let mut reader = DecryptingReader::new(
+//!         Salsa20::new(key, nonce, 0),
+//!         &ciphertext[..]
+//!     );
+//!     let mut plaintext = Vec::new();
+//!     reader.read_to_end(&mut plaintext).unwrap();
+//!     assert_eq!(plaintext, b"attack at dawn");
+//! }
+//! ```
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use core::task::Poll;
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use crate::Salsa20;
+
+// defines the async `EncryptingWriter` and `DecryptingReader` over the
+// `AsyncWrite` and `AsyncRead` traits in scope, the async modules only
+// implement the traits for them
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+macro_rules! async_adapters {
+    () => {
+        /// An async writer that encrypts the bytes before passing them to
+        /// the inner writer
+        #[derive(Debug)]
+        pub struct EncryptingWriter<W: AsyncWrite + Unpin> {
+            salsa20: Salsa20,
+            inner: W
+        }
+
+        impl<W: AsyncWrite + Unpin> EncryptingWriter<W> {
+            /// creates an encrypting writer
+            /// # Arguments
+            /// * `salsa20` - cipher, positioned at the first byte to write
+            /// * `inner` - writer receiving the encrypted bytes
+            pub fn new(salsa20: Salsa20, inner: W) -> EncryptingWriter<W> {
+                EncryptingWriter { salsa20, inner }
+            }
+
+            /// returns a reference to the inner writer
+            pub fn get_ref(&self) -> &W {
+                &self.inner
+            }
+
+            /// returns a mutable reference to the inner writer, writing to
+            /// it directly puts the cipher out of step with the written bytes
+            pub fn get_mut(&mut self) -> &mut W {
+                &mut self.inner
+            }
+
+            /// returns the inner writer
+            pub fn into_inner(self) -> W {
+                self.inner
+            }
+        }
+
+        /// An async reader that decrypts the bytes read from the inner reader
+        #[derive(Debug)]
+        pub struct DecryptingReader<R: AsyncRead + Unpin> {
+            salsa20: Salsa20,
+            inner: R
+        }
+
+        impl<R: AsyncRead + Unpin> DecryptingReader<R> {
+            /// creates a decrypting reader
+            /// # Arguments
+            /// * `salsa20` - cipher, positioned at the first byte to read
+            /// * `inner` - reader of the encrypted bytes
+            pub fn new(salsa20: Salsa20, inner: R) -> DecryptingReader<R> {
+                DecryptingReader { salsa20, inner }
+            }
+
+            /// returns a reference to the inner reader
+            pub fn get_ref(&self) -> &R {
+                &self.inner
+            }
+
+            /// returns a mutable reference to the inner reader, reading from
+            /// it directly puts the cipher out of step with the read bytes
+            pub fn get_mut(&mut self) -> &mut R {
+                &mut self.inner
+            }
+
+            /// returns the inner reader
+            pub fn into_inner(self) -> R {
+                self.inner
+            }
+        }
+    };
+}
+
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "futures-io")]
+pub mod futures;
+
+// encrypts the start of `buf` and passes it to `write`, which returns its
+// result and the number of bytes taken by the inner writer; the inner writer
+// may take only a part of the bytes, so the cipher is restored and moved
+// past the bytes taken only, its sequence is used again by the next call
+fn encrypt_with<T, F>(salsa20: &mut Salsa20, buf: &[u8], write: F) -> T
+    where F: FnOnce(&[u8]) -> (T, usize)
+{
+    let mut buffer = [0; 4096];
+    let len = buf.len().min(buffer.len());
+    let saved = *salsa20;
+    salsa20
+        .encrypt_b2b(&buf[..len], &mut buffer[..len])
+        .expect("buffers of the same length");
+
+    let (result, written) = write(&buffer[..len]);
+    if written != len {
+        // not `seek`, which panics when the bytes taken end the sequence
+        *salsa20 = saved;
+        salsa20.generate(&mut buffer[..written]);
+    }
+    result
+}
+
+// encrypts the start of `buf` and passes it to `poll_write` of the inner
+// async writer, the bytes not taken when it is pending or fails are
+// encrypted again by the next call
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+fn poll_encrypt<F>(salsa20: &mut Salsa20, buf: &[u8], poll_write: F)
+    -> Poll<Result<usize>>
+    where F: FnOnce(&[u8]) -> Poll<Result<usize>>
+{
+    encrypt_with(salsa20, buf, |data| {
+        let poll = poll_write(data);
+        let written = match poll {
+            Poll::Ready(Ok(written)) => written,
+            _ => 0
+        };
+        (poll, written)
+    })
+}
+
+/// A writer that encrypts the bytes before passing them to the inner writer
+#[derive(Debug)]
+pub struct EncryptingWriter<W: Write> {
+    salsa20: Salsa20,
+    inner: W
+}
+
+impl<W: Write> EncryptingWriter<W> {
+    /// creates an encrypting writer
+    /// # Arguments
+    /// * `salsa20` - cipher, positioned at the first byte to write
+    /// * `inner` - writer receiving the encrypted bytes
+    pub fn new(salsa20: Salsa20, inner: W) -> EncryptingWriter<W> {
+        EncryptingWriter { salsa20, inner }
+    }
+
+    /// returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// returns a mutable reference to the inner writer, writing to it
+    /// directly puts the cipher out of step with the written bytes
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// returns the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for EncryptingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let inner = &mut self.inner;
+        encrypt_with(&mut self.salsa20, buf, |data| {
+            let result = inner.write(data);
+            let written = *result.as_ref().unwrap_or(&0);
+            (result, written)
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader that decrypts the bytes read from the inner reader
+#[derive(Debug)]
+pub struct DecryptingReader<R: Read> {
+    salsa20: Salsa20,
+    inner: R,
+    start: u128,
+    inner_start: Option<u64>,
+    read: u64
+}
+
+impl<R: Read> DecryptingReader<R> {
+    /// creates a decrypting reader, the cipher position at creation matches
+    /// the position of the inner reader at creation, seeking the inner
+    /// reader `n` bytes away from it moves the cipher `n` bytes away as well
+    /// # Arguments
+    /// * `salsa20` - cipher, positioned at the first byte to read
+    /// * `inner` - reader of the encrypted bytes
+    pub fn new(salsa20: Salsa20, inner: R) -> DecryptingReader<R> {
+        let start = salsa20.current_pos();
+        DecryptingReader { salsa20, inner, start, inner_start: None, read: 0 }
+    }
+
+    /// returns a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// returns a mutable reference to the inner reader, reading from it
+    /// directly puts the cipher out of step with the read bytes
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// returns the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.salsa20.encrypt(&mut buf[..len]);
+        if self.inner_start.is_none() {
+            self.read += len as u64;
+        }
+        Ok(len)
+    }
+}
+
+impl<R: Read + Seek> Seek for DecryptingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        // the position of the inner reader at creation, found from the
+        // bytes read before the first seek
+        let inner_start = match self.inner_start {
+            Some(inner_start) => inner_start,
+            None => {
+                let inner_start = self.inner.stream_position()?
+                    .saturating_sub(self.read);
+                self.inner_start = Some(inner_start);
+                inner_start
+            }
+        };
+
+        let position = self.inner.seek(pos)?;
+        let byte_pos = (self.start + u128::from(position))
+            .checked_sub(u128::from(inner_start))
+            .ok_or_else(|| Error::new(
+                ErrorKind::InvalidInput,
+                "position before the start of the sequence"
+            ))?;
+        self.salsa20.seek(byte_pos);
+        Ok(position)
+    }
+}
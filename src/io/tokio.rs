@@ -0,0 +1,54 @@
+//! # tokio
+//! `tokio::io` adapters that encrypt bytes written to an `AsyncWrite` and
+//! decrypt bytes read from an `AsyncRead` with Salsa20
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::io::Result;
+use ::tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use crate::Salsa20;
+use super::poll_encrypt;
+
+async_adapters!();
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for EncryptingWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let inner = &mut this.inner;
+        poll_encrypt(&mut this.salsa20, buf, |data| {
+            Pin::new(inner).poll_write(cx, data)
+        })
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>)
+        -> Poll<Result<()>>
+    {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>)
+        -> Poll<Result<()>>
+    {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for DecryptingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>
+    ) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            this.salsa20.encrypt(&mut buf.filled_mut()[filled..]);
+        }
+        poll
+    }
+}
@@ -44,10 +44,58 @@
 //! ```
 
 #![no_std]
+#![deny(unsafe_code)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod rounds;
 mod utils;
+mod encoding;
+mod parallel;
+#[cfg(feature = "rand_core")]
+mod rng;
+mod xsalsa20;
+mod chacha20;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[allow(unsafe_code)]
+mod simd;
+#[cfg(feature = "cipher")]
+mod traits;
+#[cfg(feature = "scrypt")]
+pub mod scrypt;
+pub mod poly1305;
+pub mod secretbox;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod container;
+#[cfg(feature = "zeroize")]
+mod secret;
+use core::convert::TryFrom;
 use core::fmt;
+use core::marker::PhantomData;
 use crate::utils::{u8_to_u32, xor_from_slice};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub use crate::rounds::{Rounds, R8, R12, R20};
+pub use crate::xsalsa20::XSalsa20;
+pub use crate::chacha20::{hchacha20, ChaCha20, ChaCha20Ietf, XChaCha20};
+#[cfg(feature = "rand_core")]
+pub use crate::rng::Salsa20Rng;
+#[cfg(feature = "rand_core")]
+pub use rand_core;
+#[cfg(feature = "cipher")]
+pub use cipher;
+#[cfg(feature = "zeroize")]
+pub use crate::secret::Secret;
+#[cfg(feature = "zeroize")]
+use crate::secret::Wipe;
+#[cfg(feature = "zeroize")]
+pub use zeroize;
 
 fn quarterround(y0: u32, y1: u32, y2: u32, y3: u32) -> [u32; 4] {
     let y1 = y1 ^ y0.wrapping_add(y3).rotate_left(7);
@@ -94,6 +142,50 @@ fn doubleround(y: [u32; 16]) -> [u32; 16] {
     rowround(columnround(y))
 }
 
+/// computes the Salsa20 core of the 16-word `input`
+/// # Arguments
+/// * `input` - 16-word sequence
+/// * `rounds` - number of rounds, 8, 12 or 20 for Salsa20/8, /12 and /20
+/// # Panics
+/// Panics if `rounds` is odd
+pub fn salsa20_core(input: &[u32; 16], rounds: usize) -> [u32; 16] {
+    assert!(rounds.is_multiple_of(2), "number of rounds must be even");
+
+    let mut output = (0..rounds / 2)
+        .fold(*input, |block, _| doubleround(block));
+    output
+        .iter_mut()
+        .zip(input.iter())
+        .for_each(|(value, &input_value)| {
+            *value = value.wrapping_add(input_value);
+        });
+
+    output
+}
+
+/// computes the Salsa20 hash of the 64-byte `input`, the byte-oriented
+/// variant of `salsa20_core` with little-endian words
+/// # Arguments
+/// * `input` - 64-byte sequence
+/// * `rounds` - number of rounds, 8, 12 or 20 for Salsa20/8, /12 and /20
+/// # Panics
+/// Panics if `rounds` is odd
+pub fn salsa20_hash(input: &[u8; 64], rounds: usize) -> [u8; 64] {
+    let mut input_u32 = [0; 16];
+    u8_to_u32(&input[..], &mut input_u32);
+
+    let mut buffer = [0; 64];
+    salsa20_core(&input_u32, rounds)
+        .iter()
+        .enumerate()
+        .for_each(|(index, value)| {
+            let offset = index * 4;
+            buffer[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        });
+
+    buffer
+}
+
 #[derive(Clone, Copy)]
 struct Overflow {
     buffer: [u8; 64],
@@ -112,56 +204,410 @@ impl Overflow {
         self.offset += buffer.len();
         modifier(buffer, &self.buffer[offset..self.offset]);
     }
+
+    fn apply<F, G>(&mut self, buffer: &mut [u8], modifier: &F, mut fill: G)
+        where F: Fn(&mut [u8], &[u8]), G: FnMut(&mut [u8])
+    {
+        let buffer_len = buffer.len();
+        let overflow_len = 64 - self.offset;
+
+        if overflow_len != 0 {
+            if buffer_len >= overflow_len {
+                self.modify(&mut buffer[..overflow_len], modifier);
+            } else {
+                self.modify(&mut buffer[..], modifier);
+                return;
+            }
+        }
+
+        let last_block_offset = buffer_len - (buffer_len - overflow_len) % 64;
+
+        let mut keystream = [0; 512];
+        for chunk in buffer[overflow_len..last_block_offset].chunks_mut(512) {
+            let keystream = &mut keystream[..chunk.len()];
+            fill(keystream);
+            modifier(chunk, keystream);
+        }
+        // only the part of the scratch filled above holds the sequence
+        #[cfg(feature = "zeroize")]
+        keystream[..(last_block_offset - overflow_len).min(512)].zeroize();
+
+        if last_block_offset != buffer_len {
+            fill(&mut self.buffer);
+            self.offset = 0;
+            self.modify(&mut buffer[last_block_offset..], modifier);
+        }
+    }
 }
 
+#[cfg(feature = "zeroize")]
+impl Zeroize for Overflow {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+    }
+}
+
+// the buffer holds the sequence, so it is left out
 impl fmt::Debug for Overflow {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
             .debug_struct("Overflow")
-            .field("buffer", &&self.buffer[..])
             .field("offset", &self.offset)
-            .finish()
+            .finish_non_exhaustive()
+    }
+}
+
+// shows the secret values, only built by the `dangerous_debug` methods
+struct DangerousDebug<'a, T>(&'a T);
+
+/// Error returned when a request would run past the end of the sequence,
+/// after which the 64-bit block counter wraps and the sequence repeats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CounterOverflow;
+
+impl fmt::Display for CounterOverflow {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("salsa20 counter overflow")
+    }
+}
+
+/// Error returned when the input and output buffers have different lengths
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthMismatch;
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("input and output lengths differ")
     }
 }
 
+/// Error returned by the fallible constructors and operations of the crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// the key is neither 16 nor 32 bytes long
+    InvalidKeyLength,
+    /// the nonce has a different length than the cipher takes
+    InvalidNonceLength,
+    /// the request would run past the end of the sequence
+    CounterOverflow,
+    /// the text is not a valid hexadecimal or Base64 form
+    InvalidEncoding,
+    /// the output buffer is too short for the result
+    BufferTooSmall
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Error::InvalidKeyLength => "invalid key length",
+            Error::InvalidNonceLength => "invalid nonce length",
+            Error::CounterOverflow => "salsa20 counter overflow",
+            Error::InvalidEncoding => "invalid encoding",
+            Error::BufferTooSmall => "buffer too small"
+        })
+    }
+}
+
+impl From<CounterOverflow> for Error {
+    fn from(_: CounterOverflow) -> Error {
+        Error::CounterOverflow
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CounterOverflow {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LengthMismatch {}
+
 /// Key for Salsa20, 32-byte or 16-byte sequence
-#[derive(Clone, Copy, Debug)]
+///
+/// The `Debug` output shows only the length of the key, see
+/// [`Key::dangerous_debug`]
+///
+/// The key is a plain `Copy` value taken by value by every constructor, so
+/// it is not wiped on drop: making it move-only would break all of its
+/// users. The ciphers keep only the state derived from it, which a `Secret`
+/// cipher wipes on drop, and a key held by the caller is wiped with
+/// `Zeroize::zeroize`.
+#[derive(Clone, Copy)]
 pub enum Key {
     Key16([u8; 16]),
     Key32([u8; 32])
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Generator {
-    init_matrix: [u32; 16],
-    cround_matrix: [u32; 16],
-    dround_values: [u32; 4],
-    counter: u64
+impl Key {
+    /// creates a key from a slice
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    pub fn from_slice(key: &[u8]) -> Result<Key, Error> {
+        if let Ok(key) = <[u8; 32]>::try_from(key) {
+            return Ok(Key::Key32(key));
+        }
+        match <[u8; 16]>::try_from(key) {
+            Ok(key) => Ok(Key::Key16(key)),
+            Err(_) => Err(Error::InvalidKeyLength)
+        }
+    }
+
+    /// creates a key from its hexadecimal form, in upper or lower case
+    /// # Arguments
+    /// * `key` - 64 or 32 hexadecimal digits of a 32-byte or 16-byte key
+    pub fn from_hex(key: &str) -> Result<Key, Error> {
+        Key::decode(key, encoding::decode_hex)
+    }
+
+    /// creates a key from its padded Base64 form
+    /// # Arguments
+    /// * `key` - 44 or 24 symbols of a 32-byte or 16-byte key
+    pub fn from_base64(key: &str) -> Result<Key, Error> {
+        Key::decode(key, encoding::decode_base64)
+    }
+
+    /// writes the lowercase hexadecimal form of the key, which must not end
+    /// up in logs, to the start of `buffer` and returns it
+    /// # Arguments
+    /// * `buffer` - output, at least 64 or 32 bytes for a 32-byte or 16-byte
+    ///   key
+    pub fn to_hex<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a str, Error> {
+        encoding::encode_hex(self.as_bytes(), buffer)
+    }
+
+    /// writes the padded Base64 form of the key, which must not end up in
+    /// logs, to the start of `buffer` and returns it
+    /// # Arguments
+    /// * `buffer` - output, at least 44 or 24 bytes for a 32-byte or 16-byte
+    ///   key
+    pub fn to_base64<'a>(&self, buffer: &'a mut [u8])
+        -> Result<&'a str, Error>
+    {
+        encoding::encode_base64(self.as_bytes(), buffer)
+    }
+
+    /// returns a `Debug` view that shows the bytes of the key, which must
+    /// not end up in logs
+    pub fn dangerous_debug(&self) -> impl fmt::Debug + '_ {
+        DangerousDebug(self)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Key::Key16(key) => key,
+            Key::Key32(key) => key
+        }
+    }
+
+    fn decode<F>(key: &str, decode: F) -> Result<Key, Error>
+        where F: Fn(&str, &mut [u8]) -> Result<usize, Error>
+    {
+        let mut buffer = [0; 32];
+        let key = match decode(key, &mut buffer) {
+            Ok(len) => Key::from_slice(&buffer[..len]),
+            Err(Error::BufferTooSmall) => Err(Error::InvalidKeyLength),
+            Err(error) => Err(error)
+        };
+        #[cfg(feature = "zeroize")]
+        buffer.zeroize();
+        key
+    }
 }
 
-impl Generator {
-    fn new(key: Key, nonce: [u8; 8], counter: u64) -> Generator {
-        let mut init_matrix = [0; 16];
-        init_matrix[0] = 1634760805;
-        init_matrix[15] = 1797285236;
-        init_matrix[8] = counter as u32;
-        init_matrix[9] = (counter >> 32) as u32;
-        u8_to_u32(&nonce[..], &mut init_matrix[6..8]);
+impl TryFrom<&[u8]> for Key {
+    type Error = Error;
+
+    fn try_from(key: &[u8]) -> Result<Key, Error> {
+        Key::from_slice(key)
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, len) = match self {
+            Key::Key16(key) => ("Key16", key.len()),
+            Key::Key32(key) => ("Key32", key.len())
+        };
+        formatter
+            .debug_struct(name)
+            .field("len", &len)
+            .finish_non_exhaustive()
+    }
+}
 
-        match key {
+impl fmt::Debug for DangerousDebug<'_, Key> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
             Key::Key16(key) => {
-                u8_to_u32(&key[..], &mut init_matrix[1..5]);
-                u8_to_u32(&key[..], &mut init_matrix[11..15]);
-                init_matrix[5] = 824206446;
-                init_matrix[10] = 2036477238;
+                formatter.debug_tuple("Key16").field(key).finish()
             }
             Key::Key32(key) => {
-                u8_to_u32(&key[..16], &mut init_matrix[1..5]);
-                u8_to_u32(&key[16..], &mut init_matrix[11..15]);
-                init_matrix[5] = 857760878;
-                init_matrix[10] = 2036477234;
+                formatter.debug_tuple("Key32").field(key).finish()
             }
         }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for Key {
+    fn zeroize(&mut self) {
+        match self {
+            Key::Key16(key) => key.zeroize(),
+            Key::Key32(key) => key.zeroize()
+        }
+    }
+}
+
+/// Nonce of `N` bytes, 8 for Salsa20 and 24 for XSalsa20
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Nonce<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Nonce<N> {
+    /// creates a nonce from a slice
+    /// # Arguments
+    /// * `nonce` - `N`-byte sequence
+    pub fn from_slice(nonce: &[u8]) -> Result<Nonce<N>, Error> {
+        <[u8; N]>::try_from(nonce)
+            .map(Nonce)
+            .map_err(|_| Error::InvalidNonceLength)
+    }
+
+    /// creates a nonce from its hexadecimal form, in upper or lower case
+    /// # Arguments
+    /// * `nonce` - `2 * N` hexadecimal digits
+    pub fn from_hex(nonce: &str) -> Result<Nonce<N>, Error> {
+        Nonce::decode(nonce, encoding::decode_hex)
+    }
+
+    /// creates a nonce from its padded Base64 form
+    /// # Arguments
+    /// * `nonce` - 4 symbols for each started 3 bytes of the nonce
+    pub fn from_base64(nonce: &str) -> Result<Nonce<N>, Error> {
+        Nonce::decode(nonce, encoding::decode_base64)
+    }
+
+    /// writes the lowercase hexadecimal form of the nonce to the start of
+    /// `buffer` and returns it
+    /// # Arguments
+    /// * `buffer` - output, at least `2 * N` bytes
+    pub fn to_hex<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a str, Error> {
+        encoding::encode_hex(&self.0, buffer)
+    }
+
+    /// writes the padded Base64 form of the nonce to the start of `buffer`
+    /// and returns it
+    /// # Arguments
+    /// * `buffer` - output, at least 4 bytes for each started 3 bytes of the
+    ///   nonce
+    pub fn to_base64<'a>(&self, buffer: &'a mut [u8])
+        -> Result<&'a str, Error>
+    {
+        encoding::encode_base64(&self.0, buffer)
+    }
+
+    fn decode<F>(nonce: &str, decode: F) -> Result<Nonce<N>, Error>
+        where F: Fn(&str, &mut [u8]) -> Result<usize, Error>
+    {
+        let mut buffer = [0; N];
+        match decode(nonce, &mut buffer) {
+            Ok(len) if len == N => Ok(Nonce(buffer)),
+            Ok(_) | Err(Error::BufferTooSmall) => {
+                Err(Error::InvalidNonceLength)
+            }
+            Err(error) => Err(error)
+        }
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Nonce<N> {
+    fn from(nonce: [u8; N]) -> Nonce<N> {
+        Nonce(nonce)
+    }
+}
+
+impl<const N: usize> From<Nonce<N>> for [u8; N] {
+    fn from(nonce: Nonce<N>) -> [u8; N] {
+        nonce.0
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for Nonce<N> {
+    type Error = Error;
+
+    fn try_from(nonce: &[u8]) -> Result<Nonce<N>, Error> {
+        Nonce::from_slice(nonce)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Generator<R: Rounds> {
+    init_matrix: [u32; 16],
+    cround_matrix: [u32; 16],
+    dround_values: [u32; 4],
+    counter: u64,
+    wrapped: bool,
+    rounds: PhantomData<R>
+}
+
+// the matrices are derived from the key, so only the counter is shown
+impl<R: Rounds> fmt::Debug for Generator<R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Generator")
+            .field("counter", &self.counter)
+            .field("wrapped", &self.wrapped)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<R: Rounds> Zeroize for Generator<R> {
+    fn zeroize(&mut self) {
+        self.init_matrix.zeroize();
+        self.cround_matrix.zeroize();
+        self.dround_values.zeroize();
+    }
+}
+
+// the constant and key words of the initial matrix, the nonce and counter
+// words are left zero
+fn key_matrix(key: Key) -> [u32; 16] {
+    let mut key_matrix = [0; 16];
+    key_matrix[0] = 1634760805;
+    key_matrix[15] = 1797285236;
+
+    match key {
+        Key::Key16(key) => {
+            u8_to_u32(&key[..], &mut key_matrix[1..5]);
+            u8_to_u32(&key[..], &mut key_matrix[11..15]);
+            key_matrix[5] = 824206446;
+            key_matrix[10] = 2036477238;
+        }
+        Key::Key32(key) => {
+            u8_to_u32(&key[..16], &mut key_matrix[1..5]);
+            u8_to_u32(&key[16..], &mut key_matrix[11..15]);
+            key_matrix[5] = 857760878;
+            key_matrix[10] = 2036477234;
+        }
+    }
+
+    key_matrix
+}
+
+impl<R: Rounds> Generator<R> {
+    fn new(key: Key, nonce: [u8; 8], counter: u64) -> Generator<R> {
+        Generator::from_key_matrix(key_matrix(key), nonce, counter)
+    }
+
+    fn from_key_matrix(key_matrix: [u32; 16], nonce: [u8; 8], counter: u64)
+        -> Generator<R>
+    {
+        let mut init_matrix = key_matrix;
+        init_matrix[8] = counter as u32;
+        init_matrix[9] = (counter >> 32) as u32;
+        u8_to_u32(&nonce[..], &mut init_matrix[6..8]);
 
         let cround_matrix = columnround(init_matrix);
         let dround_values = quarterround(
@@ -171,7 +617,14 @@ impl Generator {
             cround_matrix[4]
         );
 
-        Generator { init_matrix, cround_matrix, dround_values, counter }
+        Generator {
+            init_matrix,
+            cround_matrix,
+            dround_values,
+            counter,
+            wrapped: false,
+            rounds: PhantomData
+        }
     }
 
     fn first_doubleround(&self) -> [u32; 16] {
@@ -217,8 +670,11 @@ impl Generator {
         self.cround_matrix[8] = z8;
         self.cround_matrix[12] = z12;
 
-        if counter > 0xffffffff_u64 {
-            self.init_matrix[9] = (counter >> 32) as u32;
+        // the high word moves in both directions when seeking, so the
+        // dependent values are rebuilt whenever it changes
+        let high = (counter >> 32) as u32;
+        if high != self.init_matrix[9] {
+            self.init_matrix[9] = high;
             let [z5, z9, z13, z1] = quarterround(
                 self.init_matrix[5],
                 self.init_matrix[9],
@@ -227,6 +683,7 @@ impl Generator {
             );
 
             self.cround_matrix[1] = z1;
+            self.cround_matrix[5] = z5;
             self.cround_matrix[9] = z9;
             self.cround_matrix[13] = z13;
 
@@ -239,68 +696,249 @@ impl Generator {
         }
     }
 
+    fn doublerounds(&self) -> [u32; 16] {
+        (1..R::DOUBLEROUNDS)
+            .fold(self.first_doubleround(), |block, _| doubleround(block))
+    }
+
+    fn next_words(&mut self) -> [u32; 16] {
+        let mut words = self.doublerounds();
+        words
+            .iter_mut()
+            .zip(self.init_matrix.iter())
+            .for_each(|(value, &init_value)| {
+                *value = value.wrapping_add(init_value);
+            });
+
+        self.advance(1);
+        words
+    }
+
     fn next(&mut self) -> [u8; 64] {
         let mut buffer = [0; 64];
-        (0..9)
-            .fold(self.first_doubleround(), |block, _| doubleround(block))
+        self.next_words()
             .iter()
-            .zip(self.init_matrix.iter())
-            .enumerate()
-            .for_each(|(index, (drounds_value, &init_value))| {
-                let offset = index * 4;
-                let sum = drounds_value.wrapping_add(init_value);
-                buffer[offset..offset + 4].copy_from_slice(&sum.to_le_bytes());
+            .zip(buffer.chunks_exact_mut(4))
+            .for_each(|(value, bytes)| {
+                bytes.copy_from_slice(&value.to_le_bytes());
             });
 
-        self.set_counter(self.counter.wrapping_add(1));
         buffer
     }
+
+    fn set_nonce(&mut self, nonce: [u8; 8]) {
+        u8_to_u32(&nonce[..], &mut self.init_matrix[6..8]);
+
+        // the nonce words only take part in the last two quarterrounds of
+        // the columnround, the first two are kept
+        let [z10, z14, z2, z6] = quarterround(
+            self.init_matrix[10],
+            self.init_matrix[14],
+            self.init_matrix[2],
+            self.init_matrix[6]
+        );
+        let [z15, z3, z7, z11] = quarterround(
+            self.init_matrix[15],
+            self.init_matrix[3],
+            self.init_matrix[7],
+            self.init_matrix[11]
+        );
+        self.cround_matrix[2] = z2;
+        self.cround_matrix[3] = z3;
+        self.cround_matrix[6] = z6;
+        self.cround_matrix[7] = z7;
+        self.cround_matrix[10] = z10;
+        self.cround_matrix[11] = z11;
+        self.cround_matrix[14] = z14;
+        self.cround_matrix[15] = z15;
+
+        self.dround_values = quarterround(
+            self.cround_matrix[5],
+            z6,
+            z7,
+            self.cround_matrix[4]
+        );
+    }
+
+    fn advance(&mut self, blocks: u64) {
+        let (counter, wrapped) = self.counter.overflowing_add(blocks);
+        self.set_counter(counter);
+        self.wrapped |= wrapped;
+    }
+
+    fn fill(&mut self, keystream: &mut [u8]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        let keystream = {
+            let init_matrix = &self.init_matrix;
+            let len = simd::fill(init_matrix, R::DOUBLEROUNDS, keystream);
+            if len != 0 {
+                self.advance(len as u64 / 64);
+            }
+            &mut keystream[len..]
+        };
+
+        let mut blocks = keystream.chunks_exact_mut(256);
+        for chunk in &mut blocks {
+            parallel::blocks(&self.init_matrix, R::DOUBLEROUNDS, chunk);
+            self.advance(4);
+        }
+        let remainder = blocks.into_remainder();
+
+        // SSE2 only takes the blocks left after the 4-block path
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        let remainder = {
+            let len = simd::fill_blocks(
+                &self.init_matrix,
+                R::DOUBLEROUNDS,
+                remainder
+            );
+            if len != 0 {
+                self.advance(len as u64 / 64);
+            }
+            &mut remainder[len..]
+        };
+
+        for block in remainder.chunks_exact_mut(64) {
+            block.copy_from_slice(&self.next());
+        }
+    }
 }
 
-/// The Salsa20 stream cipher
+/// derives a 32-byte subkey from the `key` and a 16-byte `input` with HSalsa20
+/// # Arguments
+/// * `key` - secret key, 32-byte or 16-byte sequence
+/// * `input` - 16-byte sequence, placed where Salsa20 keeps nonce and counter
+pub fn hsalsa20(key: &Key, input: &[u8; 16]) -> [u8; 32] {
+    let mut nonce = [0; 8];
+    let mut counter = [0; 8];
+    nonce.copy_from_slice(&input[..8]);
+    counter.copy_from_slice(&input[8..]);
+
+    let counter = u64::from_le_bytes(counter);
+    let generator = Generator::<R20>::new(*key, nonce, counter);
+    let block = generator.doublerounds();
+
+    let mut buffer = [0; 32];
+    [0, 5, 10, 15, 6, 7, 8, 9]
+        .iter()
+        .enumerate()
+        .for_each(|(index, &position)| {
+            let offset = index * 4;
+            buffer[offset..offset + 4]
+                .copy_from_slice(&block[position].to_le_bytes());
+        });
+
+    buffer
+}
+
+/// The Salsa20 family stream cipher with `R` rounds
+///
+/// The `Debug` output shows only the position in the sequence, see
+/// [`Salsa::dangerous_debug`]
 #[derive(Clone, Copy, Debug)]
-pub struct Salsa20 {
-    generator: Generator,
-    overflow: Overflow
+pub struct Salsa<R: Rounds> {
+    generator: Generator<R>,
+    overflow: Overflow,
+    strict: bool
+}
+
+#[cfg(feature = "zeroize")]
+impl<R: Rounds> Wipe for Salsa<R> {
+    fn wipe(&mut self) {
+        self.generator.zeroize();
+        self.overflow.zeroize();
+    }
 }
 
-impl Salsa20 {
+impl<R: Rounds> fmt::Debug for DangerousDebug<'_, Salsa<R>> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Salsa { generator, overflow, strict } = self.0;
+        formatter
+            .debug_struct("Salsa")
+            .field("init_matrix", &generator.init_matrix)
+            .field("counter", &generator.counter)
+            .field("wrapped", &generator.wrapped)
+            .field("overflow", &&overflow.buffer[overflow.offset..])
+            .field("strict", strict)
+            .finish()
+    }
+}
+
+/// The Salsa20/8 stream cipher, reduced to 8 rounds
+#[allow(non_camel_case_types)]
+pub type Salsa20_8 = Salsa<R8>;
+
+/// The Salsa20/12 stream cipher, reduced to 12 rounds
+#[allow(non_camel_case_types)]
+pub type Salsa20_12 = Salsa<R12>;
+
+/// The Salsa20/20 stream cipher
+#[allow(non_camel_case_types)]
+pub type Salsa20_20 = Salsa<R20>;
+
+/// The Salsa20 stream cipher
+pub type Salsa20 = Salsa20_20;
+
+impl<R: Rounds> Salsa<R> {
     /// creates Salsa20 stream cipher
     /// # Arguments
     /// * `key` - secret key, 32-byte or 16-byte sequence
     /// * `nounce` - 8-byte unique sequence
     /// * `counter` - 8-byte unique number of each 64-byte block
-    pub fn new(key: Key, nonce: [u8; 8], counter: u64) -> Salsa20 {
+    pub fn new(key: Key, nonce: [u8; 8], counter: u64) -> Salsa<R> {
         let overflow = Overflow::new([0; 64], 64);
         let generator = Generator::new(key, nonce, counter);
-        Salsa20 { generator, overflow }
+        Salsa { generator, overflow, strict: false }
+    }
+
+    /// creates Salsa20 stream cipher, whose `encrypt` and `generate` panic
+    /// instead of wrapping the counter at the end of the sequence
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nounce` - 8-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new_strict(key: Key, nonce: [u8; 8], counter: u64) -> Salsa<R> {
+        Salsa { strict: true, ..Salsa::new(key, nonce, counter) }
+    }
+
+    /// creates Salsa20 stream cipher from slices
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 8-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new_from_slices(key: &[u8], nonce: &[u8], counter: u64)
+        -> Result<Salsa<R>, Error>
+    {
+        let key = Key::from_slice(key)?;
+        let nonce = <[u8; 8]>::try_from(nonce)
+            .map_err(|_| Error::InvalidNonceLength)?;
+        Ok(Salsa::new(key, nonce, counter))
     }
 
     fn modify<F>(&mut self, buffer: &mut [u8], modifier: &F)
         where F: Fn(&mut [u8], &[u8])
     {
-        let buffer_len = buffer.len();
-        let overflow_len = 64 - self.overflow.offset;
-
-        if overflow_len != 0 {
-            if buffer_len >= overflow_len {
-                self.overflow.modify(&mut buffer[..overflow_len], modifier);
-            } else {
-                self.overflow.modify(&mut buffer[..], modifier);
-                return;
-            }
+        if self.strict {
+            self.check_remaining(buffer.len()).expect("counter overflow");
         }
 
-        let last_block_offset = buffer_len - (buffer_len - overflow_len) % 64;
+        let generator = &mut self.generator;
+        self.overflow.apply(buffer, modifier, |keystream| {
+            generator.fill(keystream)
+        });
+    }
 
-        for offset in (overflow_len..last_block_offset).step_by(64) {
-            modifier(&mut buffer[offset..offset + 64], &self.generator.next());
-        }
+    /// returns a `Debug` view that shows the state derived from the key and
+    /// the unused part of the sequence, which must not end up in logs
+    pub fn dangerous_debug(&self) -> impl fmt::Debug + '_ {
+        DangerousDebug(self)
+    }
 
-        if last_block_offset != buffer_len {
-            self.overflow = Overflow::new(self.generator.next(), 0);
-            self.overflow.modify(&mut buffer[last_block_offset..], modifier);
+    fn check_remaining(&self, len: usize) -> Result<(), CounterOverflow> {
+        if self.current_pos() + len as u128 > 1 << 70 {
+            return Err(CounterOverflow);
         }
+        Ok(())
     }
 
     /// sets unique number of next 64-byte block
@@ -308,9 +946,55 @@ impl Salsa20 {
         if counter != self.generator.counter {
             self.generator.set_counter(counter);
         }
+        self.generator.wrapped = false;
         self.overflow = Overflow::new([0; 64], 64);
     }
 
+    /// switches to the sequence of the `nonce` at the same position, only
+    /// the nonce-dependent part of the state is rebuilt
+    /// # Arguments
+    /// * `nonce` - 8-byte unique sequence
+    pub fn set_nonce(&mut self, nonce: [u8; 8]) {
+        self.generator.set_nonce(nonce);
+        if self.overflow.offset == 64 {
+            return;
+        }
+
+        // the unused part of the current block is taken from the new
+        // sequence, the counter is already past that block
+        let Generator { counter, wrapped, .. } = self.generator;
+        self.generator.set_counter(counter.wrapping_sub(1));
+        self.overflow.buffer = self.generator.next();
+        self.generator.wrapped = wrapped;
+    }
+
+    /// moves to the byte `byte_pos` of the sequence, so the next call of
+    /// `encrypt` or `generate` may start in the middle of a 64-byte block
+    /// # Arguments
+    /// * `byte_pos` - position in the sequence, less than 2^70
+    /// # Panics
+    /// Panics if `byte_pos` is past the end of the sequence
+    pub fn seek(&mut self, byte_pos: u128) {
+        assert!(byte_pos < 1 << 70, "byte position out of range");
+        self.set_counter((byte_pos >> 6) as u64);
+
+        let offset = (byte_pos & 0x3f) as usize;
+        if offset != 0 {
+            self.overflow = Overflow::new(self.generator.next(), offset);
+        }
+    }
+
+    /// returns the position in bytes of the next byte of the sequence
+    pub fn current_pos(&self) -> u128 {
+        let wrapped = u128::from(self.generator.wrapped) << 64;
+        let blocks = u128::from(self.generator.counter) + wrapped;
+        match self.overflow.offset {
+            64 => blocks << 6,
+            // the counter is already past the block kept in the overflow
+            offset => (blocks - 1) << 6 | offset as u128
+        }
+    }
+
     /// generates sequence to `buffer` with `nonce` under the `key`
     pub fn generate(&mut self, buffer: &mut [u8]) {
         self.modify(buffer, &<[u8]>::copy_from_slice);
@@ -320,6 +1004,110 @@ impl Salsa20 {
     pub fn encrypt(&mut self, buffer: &mut [u8]) {
         self.modify(buffer, &xor_from_slice);
     }
+
+    /// encrypts an `input` to an `output` of the same length with `nonce`
+    /// under the `key`
+    pub fn encrypt_b2b(&mut self, input: &[u8], output: &mut [u8])
+        -> Result<(), LengthMismatch>
+    {
+        if input.len() != output.len() {
+            return Err(LengthMismatch);
+        }
+
+        output.copy_from_slice(input);
+        self.encrypt(output);
+        Ok(())
+    }
+
+    /// generates sequence to `buffer` with `nonce` under the `key`, fails
+    /// without changing the `buffer` if it would run past the end of the
+    /// sequence
+    pub fn try_generate(&mut self, buffer: &mut [u8])
+        -> Result<(), CounterOverflow>
+    {
+        self.check_remaining(buffer.len())?;
+        self.generate(buffer);
+        Ok(())
+    }
+
+    /// encrypts a `buffer` with `nonce` under the `key`, fails without
+    /// changing the `buffer` if it would run past the end of the sequence
+    pub fn try_encrypt(&mut self, buffer: &mut [u8])
+        -> Result<(), CounterOverflow>
+    {
+        self.check_remaining(buffer.len())?;
+        self.encrypt(buffer);
+        Ok(())
+    }
+}
+
+/// The key schedule of the Salsa20 family stream cipher with `R` rounds,
+/// which creates ciphers for many nonces under one key
+///
+/// ## Example
+/// ```
+/// extern crate rust_salsa20;
+/// use rust_salsa20::{Salsa20, Salsa20Key, Key::Key32};
+///
+/// fn main() {
+///     let key = Salsa20Key::new(Key32([1; 32]));
+///     for nonce in 0..3u64 {
+///         let mut salsa20 = key.stream(nonce.to_le_bytes(), 0);
+///         let mut buffer = [0; 10];
+///         salsa20.encrypt(&mut buffer);
+///     }
+/// }
+/// ```
+///
+/// The `Debug` output does not show the key
+#[derive(Clone)]
+pub struct SalsaKey<R: Rounds> {
+    key_matrix: [u32; 16],
+    rounds: PhantomData<R>
+}
+
+/// The key schedule of the Salsa20/20 stream cipher
+pub type Salsa20Key = SalsaKey<R20>;
+
+impl<R: Rounds> fmt::Debug for SalsaKey<R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("SalsaKey").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<R: Rounds> Drop for SalsaKey<R> {
+    fn drop(&mut self) {
+        self.key_matrix.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<R: Rounds> ZeroizeOnDrop for SalsaKey<R> {}
+
+impl<R: Rounds> SalsaKey<R> {
+    /// precomputes the key-dependent part of the state
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    pub fn new(key: Key) -> SalsaKey<R> {
+        SalsaKey { key_matrix: key_matrix(key), rounds: PhantomData }
+    }
+
+    /// creates Salsa20 stream cipher under the key
+    /// # Arguments
+    /// * `nonce` - 8-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn stream(&self, nonce: [u8; 8], counter: u64) -> Salsa<R> {
+        Salsa {
+            generator: Generator::from_key_matrix(
+                self.key_matrix,
+                nonce,
+                counter
+            ),
+            overflow: Overflow::new([0; 64], 64),
+            strict: false
+        }
+    }
 }
 
 #[cfg(test)]
@@ -431,6 +1219,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn salsa20_hash_test() {
+        test([0; 64], [0; 64]);
+
+        test([
+            211, 159, 13, 115, 76, 55, 82, 183, 3, 117, 222, 37, 191, 187,
+            234, 136, 49, 237, 179, 48, 1, 106, 178, 219, 175, 199, 166, 48,
+            86, 16, 179, 207, 31, 240, 32, 63, 15, 83, 93, 161, 116, 147, 48,
+            113, 238, 55, 204, 36, 79, 201, 235, 79, 3, 81, 156, 47, 203, 26,
+            244, 243, 88, 118, 104, 54
+        ], [
+            109, 42, 178, 168, 156, 240, 248, 238, 168, 196, 190, 203, 26,
+            110, 170, 154, 29, 29, 150, 26, 150, 30, 235, 249, 190, 163, 251,
+            48, 69, 144, 51, 57, 118, 40, 152, 157, 180, 57, 27, 94, 107, 42,
+            236, 35, 27, 111, 114, 114, 219, 236, 232, 135, 111, 155, 110, 18,
+            24, 232, 95, 158, 179, 19, 48, 202
+        ]);
+
+        test([
+            88, 118, 104, 54, 79, 201, 235, 79, 3, 81, 156, 47, 203, 26, 244,
+            243, 191, 187, 234, 136, 211, 159, 13, 115, 76, 55, 82, 183, 3,
+            117, 222, 37, 86, 16, 179, 207, 49, 237, 179, 48, 1, 106, 178, 219,
+            175, 199, 166, 48, 238, 55, 204, 36, 31, 240, 32, 63, 15, 83, 93,
+            161, 116, 147, 48, 113
+        ], [
+            179, 19, 48, 202, 219, 236, 232, 135, 111, 155, 110, 18, 24, 232,
+            95, 158, 26, 110, 170, 154, 109, 42, 178, 168, 156, 240, 248, 238,
+            168, 196, 190, 203, 69, 144, 51, 57, 29, 29, 150, 26, 150, 30, 235,
+            249, 190, 163, 251, 48, 27, 111, 114, 114, 118, 40, 152, 157, 180,
+            57, 27, 94, 107, 42, 236, 35
+        ]);
+
+        fn test(input_data: [u8; 64], expected_data: [u8; 64]) {
+            assert_eq!(salsa20_hash(&input_data, 20)[..], expected_data[..]);
+
+            let mut input_data_u32 = [0; 16];
+            let mut expected_data_u32 = [0; 16];
+            u8_to_u32(&input_data, &mut input_data_u32);
+            u8_to_u32(&expected_data, &mut expected_data_u32);
+            assert_eq!(salsa20_core(&input_data_u32, 20), expected_data_u32);
+        }
+    }
+
+    #[test]
+    fn salsa20_core_generator_test() {
+        let key = Key::Key32([
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 201, 202,
+            203, 204, 205, 206, 207, 208, 209, 210, 211, 212, 213, 214, 215, 216
+        ]);
+        let nonce = [101, 102, 103, 104, 105, 106, 107, 108];
+
+        test(Generator::<R8>::new(key, nonce, 0xffffffff), 8);
+        test(Generator::<R12>::new(key, nonce, 0xffffffff), 12);
+        test(Generator::<R20>::new(key, nonce, 0xffffffff), 20);
+
+        fn test<R: Rounds>(mut generator: Generator<R>, rounds: usize) {
+            let mut input_data = [0; 64];
+            let init_matrix = generator.init_matrix;
+            init_matrix.iter().enumerate().for_each(|(index, value)| {
+                let offset = index * 4;
+                input_data[offset..offset + 4]
+                    .copy_from_slice(&value.to_le_bytes());
+            });
+
+            let buffer = generator.next();
+            assert_eq!(salsa20_hash(&input_data, rounds)[..], buffer[..]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn salsa20_core_odd_rounds_test() {
+        salsa20_core(&[0; 16], 9);
+    }
+
     #[test]
     fn create_init_matrix_test() {
         test(Key::Key16([
@@ -458,7 +1321,7 @@ mod tests {
             let counter = u64::from_le_bytes(
                 [109, 110, 111, 112, 113, 114, 115, 116]
             );
-            let generator = Generator::new(key, nonce, counter);
+            let generator = Generator::<R20>::new(key, nonce, counter);
 
             let mut expected_data_u32 = [0; 16];
             u8_to_u32(&expected_data, &mut expected_data_u32);
@@ -477,14 +1340,14 @@ mod tests {
 
         fn test(counter: u64, counter_as_u32: [u32; 2]) {
             let key = Key::Key16([0; 16]);
-            let mut generator = Generator::new(key, [0; 8], 0);
+            let mut generator = Generator::<R20>::new(key, [0; 8], 0);
             generator.set_counter(counter);
             assert_eq!(generator.init_matrix[8..10], counter_as_u32);
             assert_eq!(
                 generator.first_doubleround(),
                 doubleround(generator.init_matrix)
             );
-        };
+        }
     }
 
     #[test]
@@ -515,10 +1378,52 @@ mod tests {
             let counter = u64::from_le_bytes(
                 [109, 110, 111, 112, 113, 114, 115, 116]
             );
-            let mut generator = Generator::new(key, nonce, counter);
+            let mut generator = Generator::<R20>::new(key, nonce, counter);
 
             let buffer = generator.next();
             assert_eq!(buffer.to_vec(), expected_data.to_vec());
         }
     }
+
+    #[test]
+    fn set_nonce_test() {
+        test(Key::Key16([1; 16]), 0);
+        test(Key::Key32([2; 32]), 0x1_00000005);
+
+        fn test(key: Key, counter: u64) {
+            let nonce = [101, 102, 103, 104, 105, 106, 107, 108];
+            let mut generator = Generator::<R20>::new(key, [0; 8], counter);
+            generator.set_nonce(nonce);
+
+            let expected = Generator::<R20>::new(key, nonce, counter);
+            assert_eq!(generator.init_matrix, expected.init_matrix);
+            assert_eq!(generator.cround_matrix, expected.cround_matrix);
+            assert_eq!(generator.dround_values, expected.dround_values);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn generator_zeroize_test() {
+        let key = Key::Key32([1; 32]);
+        let mut generator = Generator::<R20>::new(key, [2; 8], 3);
+        generator.zeroize();
+
+        assert_eq!(generator.init_matrix, [0; 16]);
+        assert_eq!(generator.cround_matrix, [0; 16]);
+        assert_eq!(generator.dround_values, [0; 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn salsa_wipe_test() {
+        let mut salsa20 = Salsa20::new(Key::Key32([1; 32]), [2; 8], 0);
+        salsa20.generate(&mut [0; 10]);
+        salsa20.wipe();
+
+        assert_eq!(salsa20.generator.init_matrix, [0; 16]);
+        assert_eq!(salsa20.generator.cround_matrix, [0; 16]);
+        assert_eq!(salsa20.generator.dround_values, [0; 4]);
+        assert_eq!(salsa20.overflow.buffer, [0; 64]);
+    }
 }
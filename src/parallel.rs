@@ -0,0 +1,101 @@
+//! Portable computation of 4 consecutive Salsa20 blocks. Each state word
+//! holds the 4 blocks side by side, so every step of the rounds is the same
+//! operation on 4 independent values, which LLVM turns into vector code.
+
+type Lanes = [u32; 4];
+
+#[inline(always)]
+fn add(a: Lanes, b: Lanes) -> Lanes {
+    [
+        a[0].wrapping_add(b[0]),
+        a[1].wrapping_add(b[1]),
+        a[2].wrapping_add(b[2]),
+        a[3].wrapping_add(b[3])
+    ]
+}
+
+#[inline(always)]
+fn xor_rotl(a: Lanes, b: Lanes, shift: u32) -> Lanes {
+    [
+        a[0] ^ b[0].rotate_left(shift),
+        a[1] ^ b[1].rotate_left(shift),
+        a[2] ^ b[2].rotate_left(shift),
+        a[3] ^ b[3].rotate_left(shift)
+    ]
+}
+
+#[inline(always)]
+fn quarterround(x: &mut [Lanes; 16], [y0, y1, y2, y3]: [usize; 4]) {
+    x[y1] = xor_rotl(x[y1], add(x[y0], x[y3]), 7);
+    x[y2] = xor_rotl(x[y2], add(x[y1], x[y0]), 9);
+    x[y3] = xor_rotl(x[y3], add(x[y2], x[y1]), 13);
+    x[y0] = xor_rotl(x[y0], add(x[y3], x[y2]), 18);
+}
+
+#[inline(always)]
+fn doubleround(x: &mut [Lanes; 16]) {
+    quarterround(x, [0, 4, 8, 12]);
+    quarterround(x, [5, 9, 13, 1]);
+    quarterround(x, [10, 14, 2, 6]);
+    quarterround(x, [15, 3, 7, 11]);
+
+    quarterround(x, [0, 1, 2, 3]);
+    quarterround(x, [5, 6, 7, 4]);
+    quarterround(x, [10, 11, 8, 9]);
+    quarterround(x, [15, 12, 13, 14]);
+}
+
+/// writes 4 consecutive blocks to the 256-byte `output`, starting from the
+/// block of `init_matrix`
+pub(super) fn blocks(
+    init_matrix: &[u32; 16],
+    doublerounds: usize,
+    output: &mut [u8]
+) {
+    let counter = u64::from(init_matrix[8]) | u64::from(init_matrix[9]) << 32;
+    let mut init = [[0; 4]; 16];
+    for (lanes, &value) in init.iter_mut().zip(init_matrix.iter()) {
+        *lanes = [value; 4];
+    }
+    let counters = [0, 1, 2, 3].map(|lane| counter.wrapping_add(lane));
+    init[8] = counters.map(|block_counter| block_counter as u32);
+    init[9] = counters.map(|block_counter| (block_counter >> 32) as u32);
+
+    let mut x = init;
+    for _ in 0..doublerounds {
+        doubleround(&mut x);
+    }
+
+    for (index, (&lanes, &init_lanes)) in x.iter().zip(&init).enumerate() {
+        for (lane, &value) in add(lanes, init_lanes).iter().enumerate() {
+            let offset = lane * 64 + index * 4;
+            output[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Generator, Key, R8, R20, Rounds};
+
+    #[test]
+    fn blocks_test() {
+        test::<R20>(0);
+        test::<R20>(0xfffffffe);
+        test::<R20>(u64::MAX - 1);
+        test::<R8>(0x1_00000000);
+
+        fn test<R: Rounds>(counter: u64) {
+            let key = Key::Key16([5; 16]);
+            let nonce = [1, 2, 3, 4, 5, 6, 7, 8];
+            let mut generator = Generator::<R>::new(key, nonce, counter);
+
+            let mut buffer = [0; 256];
+            blocks(&generator.init_matrix, R::DOUBLEROUNDS, &mut buffer);
+            for block in buffer.chunks_exact(64) {
+                assert_eq!(block[..], generator.next()[..]);
+            }
+        }
+    }
+}
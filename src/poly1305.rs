@@ -0,0 +1,251 @@
+//! # Poly1305
+//! Poly1305 one-time authenticator
+//! ([RFC 8439](https://tools.ietf.org/html/rfc8439#section-2.5)),
+//! a key must never be used to authenticate more than one message
+//!
+//! ## Example
+//! ```
+//! extern crate rust_salsa20;
+//! use rust_salsa20::poly1305::{poly1305, Poly1305};
+//!
+//! fn main() {
+//!     let key = [1; 32];
+//!     let mut mac = Poly1305::new(&key);
+//!     mac.update(b"Cryptographic ");
+//!     mac.update(b"Forum Research Group");
+//!     let tag = mac.finalize();
+//!
+//!     assert_eq!(tag, poly1305(&key, b"Cryptographic Forum Research Group"));
+//! }
+//! ```
+
+use core::fmt;
+use crate::utils::{constant_time_eq, u8_to_u32};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+#[cfg(feature = "zeroize")]
+use crate::secret::Wipe;
+
+/// Length of the Poly1305 key in bytes
+pub const KEY_LEN: usize = 32;
+
+/// Length of the Poly1305 tag in bytes
+pub const TAG_LEN: usize = 16;
+
+/// Error returned when the authentication tag does not match
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyError;
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("poly1305 tag mismatch")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+fn load(bytes: &[u8]) -> u32 {
+    let mut value = [0];
+    u8_to_u32(bytes, &mut value);
+    value[0]
+}
+
+/// The incremental Poly1305 authenticator
+///
+/// The `Debug` output shows only the number of buffered bytes
+#[derive(Clone, Copy)]
+pub struct Poly1305 {
+    r: [u32; 5],
+    h: [u32; 5],
+    pad: [u32; 4],
+    buffer: [u8; 16],
+    leftover: usize
+}
+
+impl fmt::Debug for Poly1305 {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Poly1305")
+            .field("leftover", &self.leftover)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Wipe for Poly1305 {
+    fn wipe(&mut self) {
+        self.r.zeroize();
+        self.h.zeroize();
+        self.pad.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl Poly1305 {
+    /// creates Poly1305 authenticator
+    /// # Arguments
+    /// * `key` - one-time key, 32-byte sequence
+    pub fn new(key: &[u8; KEY_LEN]) -> Poly1305 {
+        let r = [
+            load(&key[0..4]) & 0x3ffffff,
+            (load(&key[3..7]) >> 2) & 0x3ffff03,
+            (load(&key[6..10]) >> 4) & 0x3ffc0ff,
+            (load(&key[9..13]) >> 6) & 0x3f03fff,
+            (load(&key[12..16]) >> 8) & 0x00fffff
+        ];
+        let mut pad = [0; 4];
+        u8_to_u32(&key[16..], &mut pad);
+
+        Poly1305 { r, h: [0; 5], pad, buffer: [0; 16], leftover: 0 }
+    }
+
+    fn block(&mut self, block: &[u8], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r;
+        let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+        let [r0, r1, r2, r3, r4] = [r0, r1, r2, r3, r4].map(u64::from);
+        let [s1, s2, s3, s4] = [s1, s2, s3, s4].map(u64::from);
+
+        let [h0, h1, h2, h3, h4] = [
+            self.h[0] + (load(&block[0..4]) & 0x3ffffff),
+            self.h[1] + ((load(&block[3..7]) >> 2) & 0x3ffffff),
+            self.h[2] + ((load(&block[6..10]) >> 4) & 0x3ffffff),
+            self.h[3] + ((load(&block[9..13]) >> 6) & 0x3ffffff),
+            self.h[4] + ((load(&block[12..16]) >> 8) | hibit)
+        ].map(u64::from);
+
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        let d1 = d1 + (d0 >> 26);
+        let d2 = d2 + (d1 >> 26);
+        let d3 = d3 + (d2 >> 26);
+        let d4 = d4 + (d3 >> 26);
+        let h0 = (d0 & 0x3ffffff) + (d4 >> 26) * 5;
+
+        self.h = [
+            (h0 & 0x3ffffff) as u32,
+            ((d1 & 0x3ffffff) + (h0 >> 26)) as u32,
+            (d2 & 0x3ffffff) as u32,
+            (d3 & 0x3ffffff) as u32,
+            (d4 & 0x3ffffff) as u32
+        ];
+    }
+
+    /// absorbs the next part of the message
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.leftover != 0 {
+            let len = data.len().min(16 - self.leftover);
+            self.buffer[self.leftover..self.leftover + len]
+                .copy_from_slice(&data[..len]);
+            self.leftover += len;
+            data = &data[len..];
+
+            if self.leftover < 16 {
+                return;
+            }
+            let buffer = self.buffer;
+            self.block(&buffer, 1 << 24);
+            self.leftover = 0;
+        }
+
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.block(block, 1 << 24);
+        }
+
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.leftover = remainder.len();
+    }
+
+    /// computes the authentication tag of the absorbed message
+    pub fn finalize(mut self) -> [u8; TAG_LEN] {
+        if self.leftover != 0 {
+            let mut buffer = [0; 16];
+            buffer[..self.leftover]
+                .copy_from_slice(&self.buffer[..self.leftover]);
+            buffer[self.leftover] = 1;
+            self.block(&buffer, 0);
+        }
+
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+        h2 += h1 >> 26;
+        h1 &= 0x3ffffff;
+        h3 += h2 >> 26;
+        h2 &= 0x3ffffff;
+        h4 += h3 >> 26;
+        h3 &= 0x3ffffff;
+        h0 += (h4 >> 26) * 5;
+        h4 &= 0x3ffffff;
+        h1 += h0 >> 26;
+        h0 &= 0x3ffffff;
+
+        // computes h - p and selects it without branches if it is not negative
+        let g0 = h0 + 5;
+        let g1 = h1 + (g0 >> 26);
+        let g2 = h2 + (g1 >> 26);
+        let g3 = h3 + (g2 >> 26);
+        let g4 = (h4 + (g3 >> 26)).wrapping_sub(1 << 26);
+        let mask = (g4 >> 31).wrapping_sub(1);
+        let select = |h: u32, g: u32| (h & !mask) | (g & 0x3ffffff & mask);
+        let [h0, h1, h2, h3, h4] = [
+            select(h0, g0),
+            select(h1, g1),
+            select(h2, g2),
+            select(h3, g3),
+            (h4 & !mask) | (g4 & mask)
+        ];
+
+        let words = [
+            h0 | (h1 << 26),
+            (h1 >> 6) | (h2 << 20),
+            (h2 >> 12) | (h3 << 14),
+            (h3 >> 18) | (h4 << 8)
+        ];
+
+        let mut tag = [0; 16];
+        let mut carry = 0;
+        for (index, (&word, &pad)) in words.iter().zip(&self.pad).enumerate() {
+            let sum = u64::from(word) + u64::from(pad) + carry;
+            let offset = index * 4;
+            carry = sum >> 32;
+            tag[offset..offset + 4]
+                .copy_from_slice(&(sum as u32).to_le_bytes());
+        }
+
+        tag
+    }
+
+    /// compares the authentication tag of the absorbed message with `tag`
+    /// in constant time
+    pub fn verify(self, tag: &[u8; TAG_LEN]) -> Result<(), VerifyError> {
+        if constant_time_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(VerifyError)
+        }
+    }
+}
+
+/// computes the authentication tag of a `message` under the one-time `key`
+pub fn poly1305(key: &[u8; KEY_LEN], message: &[u8]) -> [u8; TAG_LEN] {
+    let mut poly1305 = Poly1305::new(key);
+    poly1305.update(message);
+    poly1305.finalize()
+}
+
+/// verifies the authentication `tag` of a `message` under the one-time `key`
+/// in constant time
+pub fn verify(
+    key: &[u8; KEY_LEN],
+    message: &[u8],
+    tag: &[u8; TAG_LEN]
+) -> Result<(), VerifyError> {
+    let mut poly1305 = Poly1305::new(key);
+    poly1305.update(message);
+    poly1305.verify(tag)
+}
@@ -0,0 +1,152 @@
+use core::fmt;
+use rand_core::{CryptoRng, Error, RngCore, SeedableRng};
+use crate::{Generator, Key, R20};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The Salsa20 random number generator, the sequence of Salsa20 under a
+/// 32-byte seed with the number of the stream as the nonce
+///
+/// ## Example
+/// ```
+/// extern crate rust_salsa20;
+/// use rust_salsa20::Salsa20Rng;
+/// use rust_salsa20::rand_core::{RngCore, SeedableRng};
+///
+/// fn main() {
+///     let mut rng = Salsa20Rng::from_seed([1; 32]);
+///     let value = rng.next_u64();
+///
+///     rng.set_word_pos(0);
+///     assert_eq!(rng.next_u64(), value);
+/// }
+/// ```
+///
+/// The `Debug` output shows only the position in the sequence
+#[derive(Clone)]
+pub struct Salsa20Rng {
+    generator: Generator<R20>,
+    block: [u32; 16],
+    index: usize
+}
+
+// the buffered block is a part of the sequence, so it is left out
+impl fmt::Debug for Salsa20Rng {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Salsa20Rng")
+            .field("word_pos", &self.get_word_pos())
+            .field("stream", &self.get_stream())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Salsa20Rng {
+    fn drop(&mut self) {
+        self.generator.zeroize();
+        self.block.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Salsa20Rng {}
+
+impl Salsa20Rng {
+    /// returns the position of the next 4-byte word of the sequence
+    pub fn get_word_pos(&self) -> u128 {
+        let wrapped = u128::from(self.generator.wrapped) << 64;
+        let blocks = u128::from(self.generator.counter) + wrapped;
+        // the counter is already past the block of the unread words
+        let unread = (16 - self.index) as u128;
+        ((blocks << 4) - unread) & ((1 << 68) - 1)
+    }
+
+    /// moves to the 4-byte word `word_offset` of the sequence
+    /// # Arguments
+    /// * `word_offset` - position in the sequence, taken modulo 2^68
+    pub fn set_word_pos(&mut self, word_offset: u128) {
+        self.generator.set_counter((word_offset >> 4) as u64);
+        self.generator.wrapped = false;
+        self.index = 16;
+
+        let index = (word_offset & 0xf) as usize;
+        if index != 0 {
+            self.block = self.generator.next_words();
+            self.index = index;
+        }
+    }
+
+    /// returns the number of the stream
+    pub fn get_stream(&self) -> u64 {
+        let [low, high] = [
+            self.generator.init_matrix[6],
+            self.generator.init_matrix[7]
+        ];
+        u64::from(low) | u64::from(high) << 32
+    }
+
+    /// switches to the stream `stream` at the same word position
+    /// # Arguments
+    /// * `stream` - number of the stream, the nonce of Salsa20
+    pub fn set_stream(&mut self, stream: u64) {
+        let word_pos = self.get_word_pos();
+        self.generator.set_nonce(stream.to_le_bytes());
+        self.set_word_pos(word_pos);
+    }
+}
+
+impl RngCore for Salsa20Rng {
+    fn next_u32(&mut self) -> u32 {
+        if self.index == 16 {
+            self.block = self.generator.next_words();
+            self.index = 0;
+        }
+
+        let value = self.block[self.index];
+        self.index += 1;
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32();
+        u64::from(low) | u64::from(self.next_u32()) << 32
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // the unused bytes of the last word taken from the sequence are
+        // dropped, as with `next_u32`
+        let mut offset = 0;
+        while self.index != 16 && offset < dest.len() {
+            let len = (dest.len() - offset).min(4);
+            let value = self.next_u32().to_le_bytes();
+            dest[offset..offset + len].copy_from_slice(&value[..len]);
+            offset += len;
+        }
+
+        let dest = &mut dest[offset..];
+        let blocks_len = dest.len() - dest.len() % 64;
+        self.generator.fill(&mut dest[..blocks_len]);
+
+        for chunk in dest[blocks_len..].chunks_mut(4) {
+            let value = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&value[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for Salsa20Rng {}
+
+impl SeedableRng for Salsa20Rng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: [u8; 32]) -> Salsa20Rng {
+        let generator = Generator::new(Key::Key32(seed), [0; 8], 0);
+        Salsa20Rng { generator, block: [0; 16], index: 16 }
+    }
+}
@@ -0,0 +1,52 @@
+mod private {
+    // only the types of this module implement `Rounds`
+    pub trait Sealed {}
+}
+
+/// Number of rounds of the Salsa20 family ciphers, implemented only by
+/// `R8`, `R12` and `R20`
+///
+/// ```compile_fail
+/// extern crate rust_salsa20;
+/// use rust_salsa20::Rounds;
+///
+/// struct R0;
+///
+/// impl Rounds for R0 {
+///     const DOUBLEROUNDS: usize = 0;
+/// }
+///
+/// fn main() {}
+/// ```
+pub trait Rounds: private::Sealed {
+    /// number of doublerounds, half of the number of rounds
+    const DOUBLEROUNDS: usize;
+}
+
+/// 8 rounds, Salsa20/8
+#[derive(Clone, Copy, Debug)]
+pub struct R8;
+
+/// 12 rounds, Salsa20/12
+#[derive(Clone, Copy, Debug)]
+pub struct R12;
+
+/// 20 rounds, Salsa20/20
+#[derive(Clone, Copy, Debug)]
+pub struct R20;
+
+impl private::Sealed for R8 {}
+impl private::Sealed for R12 {}
+impl private::Sealed for R20 {}
+
+impl Rounds for R8 {
+    const DOUBLEROUNDS: usize = 4;
+}
+
+impl Rounds for R12 {
+    const DOUBLEROUNDS: usize = 6;
+}
+
+impl Rounds for R20 {
+    const DOUBLEROUNDS: usize = 10;
+}
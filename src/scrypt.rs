@@ -0,0 +1,224 @@
+//! # scrypt
+//! The scrypt password-based key derivation function
+//! ([RFC 7914](https://tools.ietf.org/html/rfc7914)) built on the
+//! Salsa20/8 core
+//!
+//! ## Example
+//! ```
+//! extern crate rust_salsa20;
+//! use rust_salsa20::scrypt::scrypt;
+//!
+//! fn main() {
+//!     let mut output = [0; 32];
+//!     scrypt(b"password", b"NaCl", 1024, 8, 1, &mut output).unwrap();
+//! }
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use sha2::Sha256;
+use crate::salsa20_core;
+use crate::utils::u8_to_u32;
+
+/// Error returned by `scrypt` when the cost parameters or the output length
+/// are out of the range allowed by RFC 7914
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidParams;
+
+impl fmt::Display for InvalidParams {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("invalid scrypt parameters")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidParams {}
+
+fn block_mix(input: &[u32], output: &mut [u32]) {
+    let blocks = input.len() / 16;
+    let mut block = [0; 16];
+    block.copy_from_slice(&input[input.len() - 16..]);
+
+    for (index, chunk) in input.chunks(16).enumerate() {
+        block
+            .iter_mut()
+            .zip(chunk.iter())
+            .for_each(|(value, input_value)| *value ^= input_value);
+        block = salsa20_core(&block, 8);
+
+        let offset = (index / 2 + (index % 2) * blocks / 2) * 16;
+        output[offset..offset + 16].copy_from_slice(&block);
+    }
+}
+
+fn integerify(block: &[u32]) -> u64 {
+    let offset = block.len() - 16;
+    u64::from(block[offset]) | u64::from(block[offset + 1]) << 32
+}
+
+fn ro_mix(block: &mut [u32], n: usize) {
+    let len = block.len();
+    let mut scratch = vec![0; len];
+    let mut memory = vec![0; len * n];
+
+    for chunk in memory.chunks_mut(len) {
+        chunk.copy_from_slice(block);
+        block_mix(chunk, block);
+    }
+
+    for _ in 0..n {
+        let index = (integerify(block) & (n as u64 - 1)) as usize;
+        block
+            .iter_mut()
+            .zip(memory[index * len..(index + 1) * len].iter())
+            .for_each(|(value, memory_value)| *value ^= memory_value);
+        block_mix(block, &mut scratch);
+        block.copy_from_slice(&scratch);
+    }
+}
+
+fn check_params(n: u64, r: u32, p: u32, output_len: usize)
+    -> Result<(usize, usize), InvalidParams>
+{
+    let r = r as u64;
+    let p = p as u64;
+    if n < 2 || !n.is_power_of_two() || r == 0 || p == 0 {
+        return Err(InvalidParams);
+    }
+    if r * p >= 1 << 30 || (r < 4 && n >= 1 << (16 * r)) {
+        return Err(InvalidParams);
+    }
+    if output_len == 0 || output_len as u64 > 32 * 0xffffffff {
+        return Err(InvalidParams);
+    }
+
+    let block_len = 32 * r;
+    let memory_size = (block_len * 4).checked_mul(n).ok_or(InvalidParams)?;
+    // allocations are limited to `isize::MAX` bytes
+    if memory_size.max(block_len * 4 * p) > isize::MAX as u64 {
+        return Err(InvalidParams);
+    }
+
+    Ok((n as usize, block_len as usize))
+}
+
+/// derives a key from `password` and `salt` to `output`
+/// # Arguments
+/// * `password` - password
+/// * `salt` - salt
+/// * `n` - CPU/memory cost, a power of 2 greater than 1
+/// * `r` - block size
+/// * `p` - parallelization
+/// * `output` - derived key buffer, from 1 to (2^32 - 1) * 32 bytes long
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8],
+    n: u64,
+    r: u32,
+    p: u32,
+    output: &mut [u8]
+) -> Result<(), InvalidParams> {
+    let (n, block_len) = check_params(n, r, p, output.len())?;
+
+    let mut buffer = vec![0; block_len * 4 * p as usize];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, 1, &mut buffer);
+
+    let mut block: Vec<u32> = vec![0; block_len];
+    for chunk in buffer.chunks_mut(block_len * 4) {
+        u8_to_u32(chunk, &mut block);
+        ro_mix(&mut block, n);
+        block.iter().enumerate().for_each(|(index, value)| {
+            let offset = index * 4;
+            chunk[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        });
+    }
+
+    pbkdf2::pbkdf2_hmac::<Sha256>(password, &buffer, 1, output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn salsa20_8_core_test() {
+        let mut input = [0; 16];
+        let mut expected_data = [0; 16];
+        u8_to_u32(&[
+            0x7e, 0x87, 0x9a, 0x21, 0x4f, 0x3e, 0xc9, 0x86, 0x7c, 0xa9, 0x40,
+            0xe6, 0x41, 0x71, 0x8f, 0x26, 0xba, 0xee, 0x55, 0x5b, 0x8c, 0x61,
+            0xc1, 0xb5, 0x0d, 0xf8, 0x46, 0x11, 0x6d, 0xcd, 0x3b, 0x1d, 0xee,
+            0x24, 0xf3, 0x19, 0xdf, 0x9b, 0x3d, 0x85, 0x14, 0x12, 0x1e, 0x4b,
+            0x5a, 0xc5, 0xaa, 0x32, 0x76, 0x02, 0x1d, 0x29, 0x09, 0xc7, 0x48,
+            0x29, 0xed, 0xeb, 0xc6, 0x8d, 0xb8, 0xb8, 0xc2, 0x5e
+        ], &mut input);
+        u8_to_u32(&[
+            0xa4, 0x1f, 0x85, 0x9c, 0x66, 0x08, 0xcc, 0x99, 0x3b, 0x81, 0xca,
+            0xcb, 0x02, 0x0c, 0xef, 0x05, 0x04, 0x4b, 0x21, 0x81, 0xa2, 0xfd,
+            0x33, 0x7d, 0xfd, 0x7b, 0x1c, 0x63, 0x96, 0x68, 0x2f, 0x29, 0xb4,
+            0x39, 0x31, 0x68, 0xe3, 0xc9, 0xe6, 0xbc, 0xfe, 0x6b, 0xc5, 0xb7,
+            0xa0, 0x6d, 0x96, 0xba, 0xe4, 0x24, 0xcc, 0x10, 0x2c, 0x91, 0x74,
+            0x5c, 0x24, 0xad, 0x67, 0x3d, 0xc7, 0x61, 0x8f, 0x81
+        ], &mut expected_data);
+
+        assert_eq!(salsa20_core(&input, 8), expected_data);
+    }
+
+    #[test]
+    fn block_mix_test() {
+        let mut input = [0; 32];
+        let mut expected_data = [0; 32];
+        u8_to_u32(&[
+            0xf7, 0xce, 0x0b, 0x65, 0x3d, 0x2d, 0x72, 0xa4, 0x10, 0x8c, 0xf5,
+            0xab, 0xe9, 0x12, 0xff, 0xdd, 0x77, 0x76, 0x16, 0xdb, 0xbb, 0x27,
+            0xa7, 0x0e, 0x82, 0x04, 0xf3, 0xae, 0x2d, 0x0f, 0x6f, 0xad, 0x89,
+            0xf6, 0x8f, 0x48, 0x11, 0xd1, 0xe8, 0x7b, 0xcc, 0x3b, 0xd7, 0x40,
+            0x0a, 0x9f, 0xfd, 0x29, 0x09, 0x4f, 0x01, 0x84, 0x63, 0x95, 0x74,
+            0xf3, 0x9a, 0xe5, 0xa1, 0x31, 0x52, 0x17, 0xbc, 0xd7, 0x89, 0x49,
+            0x91, 0x44, 0x72, 0x13, 0xbb, 0x22, 0x6c, 0x25, 0xb5, 0x4d, 0xa8,
+            0x63, 0x70, 0xfb, 0xcd, 0x98, 0x43, 0x80, 0x37, 0x46, 0x66, 0xbb,
+            0x8f, 0xfc, 0xb5, 0xbf, 0x40, 0xc2, 0x54, 0xb0, 0x67, 0xd2, 0x7c,
+            0x51, 0xce, 0x4a, 0xd5, 0xfe, 0xd8, 0x29, 0xc9, 0x0b, 0x50, 0x5a,
+            0x57, 0x1b, 0x7f, 0x4d, 0x1c, 0xad, 0x6a, 0x52, 0x3c, 0xda, 0x77,
+            0x0e, 0x67, 0xbc, 0xea, 0xaf, 0x7e, 0x89
+        ], &mut input);
+        u8_to_u32(&[
+            0xa4, 0x1f, 0x85, 0x9c, 0x66, 0x08, 0xcc, 0x99, 0x3b, 0x81, 0xca,
+            0xcb, 0x02, 0x0c, 0xef, 0x05, 0x04, 0x4b, 0x21, 0x81, 0xa2, 0xfd,
+            0x33, 0x7d, 0xfd, 0x7b, 0x1c, 0x63, 0x96, 0x68, 0x2f, 0x29, 0xb4,
+            0x39, 0x31, 0x68, 0xe3, 0xc9, 0xe6, 0xbc, 0xfe, 0x6b, 0xc5, 0xb7,
+            0xa0, 0x6d, 0x96, 0xba, 0xe4, 0x24, 0xcc, 0x10, 0x2c, 0x91, 0x74,
+            0x5c, 0x24, 0xad, 0x67, 0x3d, 0xc7, 0x61, 0x8f, 0x81, 0x20, 0xed,
+            0xc9, 0x75, 0x32, 0x38, 0x81, 0xa8, 0x05, 0x40, 0xf6, 0x4c, 0x16,
+            0x2d, 0xcd, 0x3c, 0x21, 0x07, 0x7c, 0xfe, 0x5f, 0x8d, 0x5f, 0xe2,
+            0xb1, 0xa4, 0x16, 0x8f, 0x95, 0x36, 0x78, 0xb7, 0x7d, 0x3b, 0x3d,
+            0x80, 0x3b, 0x60, 0xe4, 0xab, 0x92, 0x09, 0x96, 0xe5, 0x9b, 0x4d,
+            0x53, 0xb6, 0x5d, 0x2a, 0x22, 0x58, 0x77, 0xd5, 0xed, 0xf5, 0x84,
+            0x2c, 0xb9, 0xf1, 0x4e, 0xef, 0xe4, 0x25
+        ], &mut expected_data);
+
+        let mut output = [0; 32];
+        block_mix(&input, &mut output);
+        assert_eq!(output, expected_data);
+    }
+
+    #[test]
+    fn check_params_test() {
+        assert_eq!(check_params(16, 1, 1, 64), Ok((16, 32)));
+        assert_eq!(check_params(1024, 8, 16, 64), Ok((1024, 256)));
+
+        assert_eq!(check_params(0, 1, 1, 64), Err(InvalidParams));
+        assert_eq!(check_params(1, 1, 1, 64), Err(InvalidParams));
+        assert_eq!(check_params(1000, 1, 1, 64), Err(InvalidParams));
+        assert_eq!(check_params(1 << 16, 1, 1, 64), Err(InvalidParams));
+        assert_eq!(check_params(16, 0, 1, 64), Err(InvalidParams));
+        assert_eq!(check_params(16, 1, 0, 64), Err(InvalidParams));
+        assert_eq!(check_params(16, 1 << 15, 1 << 15, 64), Err(InvalidParams));
+        assert_eq!(check_params(16, 1, 1, 0), Err(InvalidParams));
+        assert_eq!(check_params(1 << 62, 8, 1, 64), Err(InvalidParams));
+        // 2^63 bytes of memory, more than `isize::MAX`
+        assert_eq!(check_params(1 << 53, 8, 1, 64), Err(InvalidParams));
+    }
+}
@@ -0,0 +1,77 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use zeroize::ZeroizeOnDrop;
+
+mod private {
+    // wipes the state derived from the key, only called when the `Secret`
+    // holding the cipher is dropped, so a wiped cipher is never used
+    pub trait Wipe {
+        fn wipe(&mut self);
+    }
+}
+
+pub(crate) use self::private::Wipe;
+
+/// A cipher or `Poly1305` that is not `Copy` and wipes its state on drop
+///
+/// The cipher types are `Copy`, so their state may be copied around and
+/// left in memory. A `Secret` holds one in a single place, gives it out by
+/// reference and wipes it when dropped, copies are only made with `clone`.
+///
+/// ## Example
+/// ```
+/// extern crate rust_salsa20;
+/// use rust_salsa20::{Salsa20, Secret, Key::Key32};
+///
+/// fn main() {
+///     let salsa20 = Salsa20::new(Key32([1; 32]), [2; 8], 0);
+///     let mut salsa20 = Secret::new(salsa20);
+///     let mut buffer = [0; 10];
+///     salsa20.encrypt(&mut buffer);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Secret<T: Wipe>(T);
+
+impl<T: Wipe> Secret<T> {
+    /// takes the `cipher`, which is wiped when the `Secret` is dropped
+    /// # Arguments
+    /// * `cipher` - Salsa20 family or ChaCha20 family cipher, or `Poly1305`
+    pub fn new(cipher: T) -> Secret<T> {
+        Secret(cipher)
+    }
+}
+
+impl<T: Wipe> From<T> for Secret<T> {
+    fn from(cipher: T) -> Secret<T> {
+        Secret(cipher)
+    }
+}
+
+impl<T: Wipe> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Wipe> DerefMut for Secret<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Wipe + fmt::Debug> fmt::Debug for Secret<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_tuple("Secret").field(&self.0).finish()
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe> ZeroizeOnDrop for Secret<T> {}
@@ -0,0 +1,140 @@
+//! # Secretbox
+//! Authenticated encryption with XSalsa20 and Poly1305, compatible with
+//! NaCl `crypto_secretbox` and libsodium `crypto_secretbox_easy`
+//!
+//! ## Example
+//! ```
+//! extern crate rust_salsa20;
+//! use rust_salsa20::secretbox::{seal_in_place, open_in_place};
+//!
+//! fn main() {
+//!     let key = [1; 32];
+//!     let nonce = [2; 24];
+//!     let mut buffer = *b"secret message";
+//!     let tag = seal_in_place(&key, &nonce, &mut buffer);
+//!
+//!     open_in_place(&key, &nonce, &mut buffer, &tag).unwrap();
+//!     assert_eq!(&buffer, b"secret message");
+//! }
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use crate::{Key, XSalsa20};
+use crate::poly1305::Poly1305;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Length of the secretbox key in bytes
+pub const KEY_LEN: usize = 32;
+
+/// Length of the secretbox nonce in bytes
+pub const NONCE_LEN: usize = 24;
+
+/// Length of the authentication tag in bytes
+pub const TAG_LEN: usize = 16;
+
+/// Error returned by `open` when the authentication tag does not match
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenError;
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("secretbox authentication failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpenError {}
+
+fn init(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN]) -> (XSalsa20, Poly1305) {
+    let mut xsalsa20 = XSalsa20::new(Key::Key32(*key), *nonce, 0);
+    let mut poly1305_key = [0; 32];
+    xsalsa20.generate(&mut poly1305_key);
+    let poly1305 = Poly1305::new(&poly1305_key);
+    #[cfg(feature = "zeroize")]
+    poly1305_key.zeroize();
+    (xsalsa20, poly1305)
+}
+
+/// encrypts a `buffer` in place and returns its authentication tag
+/// # Arguments
+/// * `key` - secret key, 32-byte sequence
+/// * `nonce` - 24-byte unique sequence
+/// * `buffer` - plaintext, replaced by ciphertext
+pub fn seal_in_place(
+    key: &[u8; KEY_LEN],
+    nonce: &[u8; NONCE_LEN],
+    buffer: &mut [u8]
+) -> [u8; TAG_LEN] {
+    let (mut xsalsa20, mut poly1305) = init(key, nonce);
+    xsalsa20.encrypt(buffer);
+    poly1305.update(buffer);
+    poly1305.finalize()
+}
+
+/// verifies the authentication `tag` and decrypts a `buffer` in place,
+/// the `buffer` is left untouched if the tag does not match
+/// # Arguments
+/// * `key` - secret key, 32-byte sequence
+/// * `nonce` - 24-byte unique sequence
+/// * `buffer` - ciphertext, replaced by plaintext
+/// * `tag` - authentication tag
+pub fn open_in_place(
+    key: &[u8; KEY_LEN],
+    nonce: &[u8; NONCE_LEN],
+    buffer: &mut [u8],
+    tag: &[u8; TAG_LEN]
+) -> Result<(), OpenError> {
+    let (mut xsalsa20, mut poly1305) = init(key, nonce);
+    poly1305.update(buffer);
+    poly1305.verify(tag).map_err(|_| OpenError)?;
+
+    xsalsa20.encrypt(buffer);
+    Ok(())
+}
+
+/// encrypts a `plaintext` and returns the authentication tag followed by
+/// the ciphertext
+/// # Arguments
+/// * `key` - secret key, 32-byte sequence
+/// * `nonce` - 24-byte unique sequence
+/// * `plaintext` - message to encrypt
+#[cfg(feature = "alloc")]
+pub fn seal(
+    key: &[u8; KEY_LEN],
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &[u8]
+) -> Vec<u8> {
+    let mut output = Vec::with_capacity(TAG_LEN + plaintext.len());
+    output.extend_from_slice(&[0; TAG_LEN]);
+    output.extend_from_slice(plaintext);
+
+    let tag = seal_in_place(key, nonce, &mut output[TAG_LEN..]);
+    output[..TAG_LEN].copy_from_slice(&tag);
+    output
+}
+
+/// verifies and decrypts a `ciphertext` produced by `seal`
+/// # Arguments
+/// * `key` - secret key, 32-byte sequence
+/// * `nonce` - 24-byte unique sequence
+/// * `ciphertext` - authentication tag followed by the encrypted message
+#[cfg(feature = "alloc")]
+pub fn open(
+    key: &[u8; KEY_LEN],
+    nonce: &[u8; NONCE_LEN],
+    ciphertext: &[u8]
+) -> Result<Vec<u8>, OpenError> {
+    if ciphertext.len() < TAG_LEN {
+        return Err(OpenError);
+    }
+
+    let mut tag = [0; TAG_LEN];
+    tag.copy_from_slice(&ciphertext[..TAG_LEN]);
+    let mut output = ciphertext[TAG_LEN..].to_vec();
+
+    open_in_place(key, nonce, &mut output, &tag)?;
+    Ok(output)
+}
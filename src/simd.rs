@@ -0,0 +1,259 @@
+//! Vectorized Salsa20 blocks for x86_64, selected at runtime. This is the
+//! only module of the crate allowed to use `unsafe` code: the intrinsics
+//! are called only after `cpufeatures` confirms the target feature.
+
+use core::arch::x86_64::*;
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+cpufeatures::new!(sse2_cpuid, "sse2");
+
+// the state words kept in each SSE2 vector, so that the columns of the
+// matrix are the lanes of the vectors
+const DIAGONALS: [[usize; 4]; 4] = [
+    [0, 5, 10, 15],
+    [4, 9, 14, 3],
+    [8, 13, 2, 7],
+    [12, 1, 6, 11]
+];
+
+macro_rules! quarterround {
+    ($add:ident, $xor:ident, $rotl:ident, $y0:expr, $y1:expr, $y2:expr,
+     $y3:expr) => {
+        $y1 = $xor($y1, $rotl!($add($y0, $y3), 7, 25));
+        $y2 = $xor($y2, $rotl!($add($y1, $y0), 9, 23));
+        $y3 = $xor($y3, $rotl!($add($y2, $y1), 13, 19));
+        $y0 = $xor($y0, $rotl!($add($y3, $y2), 18, 14));
+    };
+}
+
+macro_rules! rotl128 {
+    ($x:expr, $left:literal, $right:literal) => {{
+        let x = $x;
+        _mm_or_si128(_mm_slli_epi32(x, $left), _mm_srli_epi32(x, $right))
+    }};
+}
+
+macro_rules! rotl256 {
+    ($x:expr, $left:literal, $right:literal) => {{
+        let x = $x;
+        _mm256_or_si256(
+            _mm256_slli_epi32(x, $left),
+            _mm256_srli_epi32(x, $right)
+        )
+    }};
+}
+
+// returns `init_matrix` with the counter moved by `blocks`
+fn input_at(init_matrix: &[u32; 16], blocks: usize) -> [u32; 16] {
+    let counter = u64::from(init_matrix[8]) | u64::from(init_matrix[9]) << 32;
+    let block_counter = counter.wrapping_add(blocks as u64);
+    let mut input = *init_matrix;
+    input[8] = block_counter as u32;
+    input[9] = (block_counter >> 32) as u32;
+    input
+}
+
+/// fills the whole 512-byte chunks at the start of `keystream` with the
+/// sequence that begins with the block of `init_matrix` using AVX2, returns
+/// the number of filled bytes, zero if the CPU has no AVX2
+pub(crate) fn fill(
+    init_matrix: &[u32; 16],
+    doublerounds: usize,
+    keystream: &mut [u8]
+) -> usize {
+    let mut len = 0;
+    if avx2_cpuid::get() {
+        for chunk in keystream.chunks_exact_mut(512) {
+            let input = input_at(init_matrix, len / 64);
+            unsafe { avx2_blocks(&input, doublerounds, chunk) };
+            len += 512;
+        }
+    }
+    len
+}
+
+/// fills the whole 64-byte blocks at the start of `keystream` one by one
+/// using SSE2, for the blocks left after the 4-block portable path, returns
+/// the number of filled bytes, zero if the CPU has no SSE2
+pub(crate) fn fill_blocks(
+    init_matrix: &[u32; 16],
+    doublerounds: usize,
+    keystream: &mut [u8]
+) -> usize {
+    let mut len = 0;
+    if sse2_cpuid::get() {
+        for block in keystream.chunks_exact_mut(64) {
+            let input = input_at(init_matrix, len / 64);
+            unsafe { sse2_block(&input, doublerounds, block) };
+            len += 64;
+        }
+    }
+    len
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn sse2_block(
+    input: &[u32; 16],
+    doublerounds: usize,
+    output: &mut [u8]
+) {
+    let load = |[w0, w1, w2, w3]: [usize; 4]| {
+        _mm_setr_epi32(
+            input[w0] as i32,
+            input[w1] as i32,
+            input[w2] as i32,
+            input[w3] as i32
+        )
+    };
+    let init = DIAGONALS.map(load);
+    let [mut a, mut b, mut c, mut d] = init;
+
+    for _ in 0..doublerounds {
+        quarterround!(_mm_add_epi32, _mm_xor_si128, rotl128, a, b, c, d);
+
+        // the rows of the matrix become the lanes after rotating the
+        // vectors, and the rotation is undone after the rowround
+        let mut b_row = _mm_shuffle_epi32(b, 0x93);
+        let mut c_row = _mm_shuffle_epi32(c, 0x4e);
+        let mut d_row = _mm_shuffle_epi32(d, 0x39);
+        quarterround!(
+            _mm_add_epi32, _mm_xor_si128, rotl128, a, d_row, c_row, b_row
+        );
+        b = _mm_shuffle_epi32(b_row, 0x39);
+        c = _mm_shuffle_epi32(c_row, 0x4e);
+        d = _mm_shuffle_epi32(d_row, 0x93);
+    }
+
+    let mut words = [[0_u32; 4]; 4];
+    for (row, &value) in [a, b, c, d].iter().enumerate() {
+        let sum = _mm_add_epi32(value, init[row]);
+        _mm_storeu_si128(words[row].as_mut_ptr() as *mut __m128i, sum);
+    }
+
+    for (row, indices) in DIAGONALS.iter().enumerate() {
+        for (lane, &index) in indices.iter().enumerate() {
+            output[index * 4..index * 4 + 4]
+                .copy_from_slice(&words[row][lane].to_le_bytes());
+        }
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn avx2_blocks(
+    input: &[u32; 16],
+    doublerounds: usize,
+    output: &mut [u8]
+) {
+    let counter = u64::from(input[8]) | u64::from(input[9]) << 32;
+    let mut low = [0_u32; 8];
+    let mut high = [0_u32; 8];
+    for lane in 0..8 {
+        let block_counter = counter.wrapping_add(lane as u64);
+        low[lane] = block_counter as u32;
+        high[lane] = (block_counter >> 32) as u32;
+    }
+
+    let mut init = input.map(|word| _mm256_set1_epi32(word as i32));
+    init[8] = _mm256_loadu_si256(low.as_ptr() as *const __m256i);
+    init[9] = _mm256_loadu_si256(high.as_ptr() as *const __m256i);
+    let mut x = init;
+
+    for _ in 0..doublerounds {
+        for &[y0, y1, y2, y3] in &[
+            [0, 4, 8, 12], [5, 9, 13, 1], [10, 14, 2, 6], [15, 3, 7, 11],
+            [0, 1, 2, 3], [5, 6, 7, 4], [10, 11, 8, 9], [15, 12, 13, 14]
+        ] {
+            quarterround!(
+                _mm256_add_epi32, _mm256_xor_si256, rotl256,
+                x[y0], x[y1], x[y2], x[y3]
+            );
+        }
+    }
+
+    let mut words = [[0_u32; 8]; 16];
+    for (index, (&value, &init_value)) in x.iter().zip(&init).enumerate() {
+        let sum = _mm256_add_epi32(value, init_value);
+        _mm256_storeu_si256(words[index].as_mut_ptr() as *mut __m256i, sum);
+    }
+
+    for (lane, block) in output.chunks_exact_mut(64).enumerate() {
+        for (index, word) in words.iter().enumerate() {
+            block[index * 4..index * 4 + 4]
+                .copy_from_slice(&word[lane].to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Generator, Key, R8, R20, Rounds};
+
+    fn new_generator<R: Rounds>(counter: u64) -> Generator<R> {
+        let key = Key::Key32([
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+            17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+        ]);
+        Generator::new(key, [1, 2, 3, 4, 5, 6, 7, 8], counter)
+    }
+
+    fn expected_data<R: Rounds>(counter: u64) -> [u8; 512] {
+        let mut generator = new_generator::<R>(counter);
+        let mut buffer = [0; 512];
+        for block in buffer.chunks_exact_mut(64) {
+            block.copy_from_slice(&generator.next());
+        }
+        buffer
+    }
+
+    const COUNTERS: [u64; 5] = [0, 1, 0xfffffffc, 0x1_00000000, u64::MAX - 2];
+
+    #[test]
+    fn sse2_block_test() {
+        if !sse2_cpuid::get() {
+            return;
+        }
+
+        for &counter in &COUNTERS {
+            let mut buffer = [0; 64];
+            let init_matrix = new_generator::<R20>(counter).init_matrix;
+            unsafe { sse2_block(&init_matrix, 10, &mut buffer) };
+            assert_eq!(buffer[..], expected_data::<R20>(counter)[..64]);
+        }
+    }
+
+    #[test]
+    fn avx2_blocks_test() {
+        if !avx2_cpuid::get() {
+            return;
+        }
+
+        for &counter in &COUNTERS {
+            let mut buffer = [0; 512];
+            let init_matrix = new_generator::<R20>(counter).init_matrix;
+            unsafe { avx2_blocks(&init_matrix, 10, &mut buffer) };
+            assert_eq!(buffer[..], expected_data::<R20>(counter)[..]);
+        }
+    }
+
+    #[test]
+    fn fill_test() {
+        for &counter in &COUNTERS {
+            for &len in &[0, 64, 128, 448, 512, 576, 1024, 1100] {
+                let mut buffer = [0; 1100];
+                let mut generator = new_generator::<R8>(counter);
+                generator.fill(&mut buffer[..len - len % 64]);
+
+                let mut expected_data = [0; 1100];
+                let mut expected_generator = new_generator::<R8>(counter);
+                for block in expected_data[..len].chunks_exact_mut(64) {
+                    block.copy_from_slice(&expected_generator.next());
+                }
+
+                assert_eq!(buffer[..], expected_data[..]);
+                assert_eq!(generator.counter, expected_generator.counter);
+                assert_eq!(generator.next(), expected_generator.next());
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use cipher::consts::{U32, U8};
+use cipher::inout::InOutBuf;
+use cipher::{
+    IvSizeUser, KeyIvInit, KeySizeUser, OverflowError, SeekNum, StreamCipher,
+    StreamCipherError, StreamCipherSeek
+};
+use crate::{Key, Rounds, Salsa};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+impl<R: Rounds> KeySizeUser for Salsa<R> {
+    type KeySize = U32;
+}
+
+impl<R: Rounds> IvSizeUser for Salsa<R> {
+    type IvSize = U8;
+}
+
+impl<R: Rounds> KeyIvInit for Salsa<R> {
+    fn new(key: &cipher::Key<Self>, iv: &cipher::Iv<Self>) -> Self {
+        Salsa::new(Key::Key32((*key).into()), (*iv).into(), 0)
+    }
+}
+
+impl<R: Rounds> StreamCipher for Salsa<R> {
+    fn try_apply_keystream_inout(
+        &mut self,
+        mut buffer: InOutBuf<'_, '_, u8>
+    ) -> Result<(), StreamCipherError> {
+        self.check_remaining(buffer.len()).map_err(|_| StreamCipherError)?;
+        #[cfg(feature = "zeroize")]
+        let used = buffer.len().min(4096);
+
+        // large chunks, so that the keystream comes from the multi-block
+        // paths of `generate`
+        let mut keystream = [0; 4096];
+        while !buffer.is_empty() {
+            let len = buffer.len().min(keystream.len());
+            let (mut chunk, rest) = buffer.split_at(len);
+            self.generate(&mut keystream[..len]);
+            chunk.xor_in2out(&keystream[..len]);
+            buffer = rest;
+        }
+        #[cfg(feature = "zeroize")]
+        keystream[..used].zeroize();
+
+        Ok(())
+    }
+}
+
+impl<R: Rounds> StreamCipherSeek for Salsa<R> {
+    fn try_current_pos<T: SeekNum>(&self) -> Result<T, OverflowError> {
+        let pos = self.current_pos();
+        T::from_block_byte((pos + 0x3f) >> 6, (pos & 0x3f) as u8, 64)
+    }
+
+    fn try_seek<T: SeekNum>(&mut self, pos: T)
+        -> Result<(), StreamCipherError>
+    {
+        let (counter, offset): (u64, u8) = pos
+            .into_block_byte(64)
+            .map_err(|_| StreamCipherError)?;
+
+        self.seek(u128::from(counter) << 6 | u128::from(offset));
+        Ok(())
+    }
+}
@@ -16,6 +16,20 @@ pub(super) fn xor_from_slice(to: &mut [u8], from: &[u8]) {
     }
 }
 
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let difference = a
+        .iter()
+        .zip(b.iter())
+        .fold(0, |difference, (a_byte, b_byte)| {
+            difference | (a_byte ^ b_byte)
+        });
+    core::hint::black_box(difference) == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,7 +43,7 @@ mod tests {
 
         fn test(bytes: &[u8], expected_values: &[u32]) {
             let mut values = [0];
-            u8_to_u32(&bytes, &mut values);
+            u8_to_u32(bytes, &mut values);
             assert_eq!(values, expected_values);
         }
     }
@@ -47,4 +61,13 @@ mod tests {
             assert_eq!(to, expected);
         }
     }
+
+    #[test]
+    fn constant_time_eq_test() {
+        assert!(constant_time_eq(&[], &[]));
+        assert!(constant_time_eq(&[1, 2, 3, 4], &[1, 2, 3, 4]));
+        assert!(!constant_time_eq(&[1, 2, 3, 4], &[1, 2, 3, 5]));
+        assert!(!constant_time_eq(&[0, 2, 3, 4], &[1, 2, 3, 4]));
+        assert!(!constant_time_eq(&[1, 2, 3, 4], &[1, 2, 3]));
+    }
 }
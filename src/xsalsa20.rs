@@ -0,0 +1,119 @@
+use core::convert::TryFrom;
+use crate::{hsalsa20, CounterOverflow, Error, Key, LengthMismatch, Salsa20};
+#[cfg(feature = "zeroize")]
+use crate::secret::Wipe;
+
+/// The XSalsa20 stream cipher, Salsa20 with a 24-byte nonce
+#[derive(Clone, Copy, Debug)]
+pub struct XSalsa20 {
+    salsa20: Salsa20
+}
+
+#[cfg(feature = "zeroize")]
+impl Wipe for XSalsa20 {
+    fn wipe(&mut self) {
+        self.salsa20.wipe();
+    }
+}
+
+impl XSalsa20 {
+    /// creates XSalsa20 stream cipher
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 24-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new(key: Key, nonce: [u8; 24], counter: u64) -> XSalsa20 {
+        let (subkey, salsa20_nonce) = XSalsa20::subkey(key, nonce);
+        let salsa20 = Salsa20::new(subkey, salsa20_nonce, counter);
+        XSalsa20 { salsa20 }
+    }
+
+    /// creates XSalsa20 stream cipher, whose `encrypt` and `generate` panic
+    /// instead of wrapping the counter at the end of the sequence
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 24-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new_strict(key: Key, nonce: [u8; 24], counter: u64) -> XSalsa20 {
+        let (subkey, salsa20_nonce) = XSalsa20::subkey(key, nonce);
+        let salsa20 = Salsa20::new_strict(subkey, salsa20_nonce, counter);
+        XSalsa20 { salsa20 }
+    }
+
+    /// creates XSalsa20 stream cipher from slices
+    /// # Arguments
+    /// * `key` - secret key, 32-byte or 16-byte sequence
+    /// * `nonce` - 24-byte unique sequence
+    /// * `counter` - 8-byte unique number of each 64-byte block
+    pub fn new_from_slices(key: &[u8], nonce: &[u8], counter: u64)
+        -> Result<XSalsa20, Error>
+    {
+        let key = Key::from_slice(key)?;
+        let nonce = <[u8; 24]>::try_from(nonce)
+            .map_err(|_| Error::InvalidNonceLength)?;
+        Ok(XSalsa20::new(key, nonce, counter))
+    }
+
+    fn subkey(key: Key, nonce: [u8; 24]) -> (Key, [u8; 8]) {
+        let mut input = [0; 16];
+        let mut salsa20_nonce = [0; 8];
+        input.copy_from_slice(&nonce[..16]);
+        salsa20_nonce.copy_from_slice(&nonce[16..]);
+
+        (Key::Key32(hsalsa20(&key, &input)), salsa20_nonce)
+    }
+
+    /// sets unique number of next 64-byte block
+    pub fn set_counter(&mut self, counter: u64) {
+        self.salsa20.set_counter(counter);
+    }
+
+    /// moves to the byte `byte_pos` of the sequence
+    /// # Arguments
+    /// * `byte_pos` - position in the sequence, less than 2^70
+    /// # Panics
+    /// Panics if `byte_pos` is past the end of the sequence
+    pub fn seek(&mut self, byte_pos: u128) {
+        self.salsa20.seek(byte_pos);
+    }
+
+    /// returns the position in bytes of the next byte of the sequence
+    pub fn current_pos(&self) -> u128 {
+        self.salsa20.current_pos()
+    }
+
+    /// generates sequence to `buffer` with `nonce` under the `key`
+    pub fn generate(&mut self, buffer: &mut [u8]) {
+        self.salsa20.generate(buffer);
+    }
+
+    /// encrypts a `buffer` with `nonce` under the `key`
+    pub fn encrypt(&mut self, buffer: &mut [u8]) {
+        self.salsa20.encrypt(buffer);
+    }
+
+    /// encrypts an `input` to an `output` of the same length with `nonce`
+    /// under the `key`
+    pub fn encrypt_b2b(&mut self, input: &[u8], output: &mut [u8])
+        -> Result<(), LengthMismatch>
+    {
+        self.salsa20.encrypt_b2b(input, output)
+    }
+
+    /// generates sequence to `buffer` with `nonce` under the `key`, fails
+    /// without changing the `buffer` if it would run past the end of the
+    /// sequence
+    pub fn try_generate(&mut self, buffer: &mut [u8])
+        -> Result<(), CounterOverflow>
+    {
+        self.salsa20.try_generate(buffer)
+    }
+
+    /// encrypts a `buffer` with `nonce` under the `key`, fails without
+    /// changing the `buffer` if it would run past the end of the sequence
+    pub fn try_encrypt(&mut self, buffer: &mut [u8])
+        -> Result<(), CounterOverflow>
+    {
+        self.salsa20.try_encrypt(buffer)
+    }
+}
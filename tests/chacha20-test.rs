@@ -0,0 +1,216 @@
+extern crate rust_salsa20;
+use rust_salsa20::{hchacha20, ChaCha20, ChaCha20Ietf, XChaCha20};
+use rust_salsa20::Key::Key32;
+
+const IETF_TEXT: &[u8] = b"Any submission to the IETF intended by the \
+    Contributor for publication as all or part of an IETF Internet-Draft or \
+    RFC and any statement made within the context of an IETF activity is \
+    considered an \"IETF Contribution\". Such statements include oral \
+    statements in IETF sessions, as well as written and electronic \
+    communications made at any time or place, which are addressed to";
+
+fn sequential_key(first: u8) -> [u8; 32] {
+    let mut key = [0; 32];
+    key.iter_mut()
+        .enumerate()
+        .for_each(|(index, value)| *value = first + index as u8);
+    key
+}
+
+// RFC 8439, section 2.3.2
+#[test]
+fn block_test() {
+    let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+    let mut chacha = ChaCha20Ietf::new(Key32(sequential_key(0)), nonce, 1);
+    let mut buffer = [0; 64];
+    chacha.generate(&mut buffer);
+
+    assert_eq!(buffer[..], decode(
+        "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+         d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+    )[..]);
+}
+
+// RFC 8439, section 2.4.2
+#[test]
+fn encrypt_test() {
+    let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+    let mut chacha = ChaCha20Ietf::new(Key32(sequential_key(0)), nonce, 1);
+    let mut buffer = b"Ladies and Gentlemen of the class of '99: If I could \
+        offer you only one tip for the future, sunscreen would be it."
+        .to_vec();
+    chacha.encrypt(&mut buffer[..7]);
+    chacha.encrypt(&mut buffer[7..70]);
+    chacha.encrypt(&mut buffer[70..]);
+
+    assert_eq!(buffer, decode(
+        "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+         f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+         07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+         5af90bbf74a35be6b40b8eedf2785e42874d"
+    ));
+}
+
+// RFC 8439, appendix A.1, test vectors #1 and #2
+#[test]
+fn keystream_test() {
+    let mut buffer = [0; 128];
+    let mut chacha = ChaCha20Ietf::new(Key32([0; 32]), [0; 12], 0);
+    chacha.generate(&mut buffer);
+
+    assert_eq!(buffer[..64], decode(
+        "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+         da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"
+    )[..]);
+    assert_eq!(buffer[64..], decode(
+        "9f07e7be5551387a98ba977c732d080dcb0f29a048e3656912c6533e32ee7aed\
+         29b721769ce64e43d57133b074d839d531ed1f28510afb45ace10a1f4b794d6f"
+    )[..]);
+
+    let mut chacha = ChaCha20::new(Key32([0; 32]), [0; 8], 0);
+    chacha.generate(&mut buffer);
+    assert_eq!(buffer[..64], decode(
+        "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+         da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"
+    )[..]);
+}
+
+// RFC 8439, appendix A.2, test vector #2
+#[test]
+fn encrypt_ietf_text_test() {
+    let mut key = [0; 32];
+    key[31] = 1;
+    let nonce = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+    let mut chacha = ChaCha20Ietf::new(Key32(key), nonce, 1);
+    let mut buffer = IETF_TEXT.to_vec();
+    chacha.encrypt(&mut buffer);
+
+    assert_eq!(buffer, decode(
+        "a3fbf07df3fa2fde4f376ca23e82737041605d9f4f4f57bd8cff2c1d4b7955ec\
+         2a97948bd3722915c8f3d337f7d370050e9e96d647b7c39f56e031ca5eb6250d\
+         4042e02785ececfa4b4bb5e8ead0440e20b6e8db09d881a7c6132f420e527950\
+         42bdfa7773d8a9051447b3291ce1411c680465552aa6c405b7764d5e87bea85a\
+         d00f8449ed8f72d0d662ab052691ca66424bc86d2df80ea41f43abf937d3259d\
+         c4b2d0dfb48a6c9139ddd7f76966e928e635553ba76c5c879d7b35d49eb2e62b\
+         0871cdac638939e25e8a1e0ef9d5280fa8ca328b351c3c765989cbcf3daa8b6c\
+         cc3aaf9f3979c92b3720fc88dc95ed84a1be059c6499b9fda236e7e818b04b0b\
+         c39c1e876b193bfe5569753f88128cc08aaa9b63d1a16f80ef2554d7189c411f\
+         5869ca52c5b83fa36ff216b9c1d30062bebcfd2dc5bce0911934fda79a86f6e6\
+         98ced759c3ff9b6477338f3da4f9cd8514ea9982ccafb341b2384dd902f3d1ab\
+         7ac61dd29c6f21ba5b862f3730e37cfdc4fd806c22f221"
+    ));
+}
+
+#[test]
+fn counter_high_word_test() {
+    test(0);
+    test(0xffffffff);
+    test(0x1234_5678_9abc_def0);
+
+    // the 64-bit counter variant continues into the word the IETF
+    // variant uses for the nonce
+    fn test(counter: u64) {
+        let key = Key32(sequential_key(7));
+        let nonce = [1, 2, 3, 4, 5, 6, 7, 8];
+        let high_word = (counter >> 32) as u32;
+        let mut ietf_nonce = [0; 12];
+        ietf_nonce[..4].copy_from_slice(&high_word.to_le_bytes());
+        ietf_nonce[4..].copy_from_slice(&nonce);
+
+        let mut expected_data = [0; 64];
+        ChaCha20Ietf::new(key, ietf_nonce, counter as u32)
+            .generate(&mut expected_data);
+
+        let mut buffer = [0; 64];
+        let mut chacha = ChaCha20::new(key, nonce, 0);
+        chacha.generate(&mut buffer[..5]);
+        chacha.set_counter(counter);
+        chacha.generate(&mut buffer);
+        assert_eq!(buffer, expected_data);
+    }
+}
+
+#[test]
+fn set_counter_test() {
+    let key = Key32(sequential_key(7));
+    let nonce = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    let mut expected_data = [0; 192];
+    ChaCha20::new(key, nonce, 0xffffffff).generate(&mut expected_data);
+
+    let mut buffer = [0; 192];
+    let mut chacha = ChaCha20::new(key, nonce, 0xffffffff);
+    chacha.generate(&mut buffer[..7]);
+    chacha.generate(&mut buffer[7..130]);
+    chacha.generate(&mut buffer[130..]);
+    assert_eq!(buffer[..], expected_data[..]);
+
+    chacha.set_counter(0x100000001);
+    chacha.generate(&mut buffer[..64]);
+    assert_eq!(buffer[..64], expected_data[128..]);
+
+    let mut expected_data = [0; 192];
+    ChaCha20Ietf::new(key, [0; 12], 0).generate(&mut expected_data);
+
+    let mut chacha = ChaCha20Ietf::new(key, [0; 12], 0);
+    chacha.generate(&mut buffer[..10]);
+    chacha.set_counter(2);
+    chacha.generate(&mut buffer[..64]);
+    assert_eq!(buffer[..64], expected_data[128..]);
+}
+
+// draft-irtf-cfrg-xchacha-03, section 2.2.1
+#[test]
+fn hchacha20_test() {
+    let input = [
+        0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00,
+        0x00, 0x31, 0x41, 0x59, 0x27
+    ];
+
+    assert_eq!(hchacha20(&Key32(sequential_key(0)), &input)[..], decode(
+        "82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc"
+    )[..]);
+}
+
+// draft-irtf-cfrg-xchacha-03, appendix A.3.2
+#[test]
+fn xchacha20_test() {
+    let mut nonce = [0; 24];
+    nonce.copy_from_slice(&decode(
+        "404142434445464748494a4b4c4d4e4f5051525354555658"
+    ));
+    let mut xchacha = XChaCha20::new(Key32(sequential_key(0x80)), nonce, 0);
+    let mut buffer = b"The dhole (pronounced \"dole\") is also known as the \
+        Asiatic wild dog, red dog, and whistling dog. It is about the size \
+        of a German shepherd but looks more like a long-legged fox. This \
+        highly elusive and skilled jumper is classified with wolves, \
+        coyotes, jackals, and foxes in the taxonomic family Canidae."
+        .to_vec();
+    xchacha.encrypt(&mut buffer[..100]);
+    xchacha.encrypt(&mut buffer[100..]);
+
+    assert_eq!(buffer, decode(
+        "4559abba4e48c16102e8bb2c05e6947f50a786de162f9b0b7e592a9b53d0d4e9\
+         8d8d6410d540a1a6375b26d80dace4fab52384c731acbf16a5923c0c48d3575d\
+         4d0d2c673b666faa731061277701093a6bf7a158a8864292a41c48e3a9b4c0da\
+         ece0f8d98d0d7e05b37a307bbb66333164ec9e1b24ea0d6c3ffddcec4f68e744\
+         3056193a03c810e11344ca06d8ed8a2bfb1e8d48cfa6bc0eb4e2464b74814240\
+         7c9f431aee769960e15ba8b96890466ef2457599852385c661f752ce20f9da0c\
+         09ab6b19df74e76a95967446f8d0fd415e7bee2a12a114c20eb5292ae7a349ae\
+         577820d5520a1f3fb62a17ce6a7e68fa7c79111d8860920bc048ef43fe84486c\
+         cb87c25f0ae045f0cce1e7989a9aa220a28bdd4827e751a24a6d5c62d790a663\
+         93b93111c1a55dd7421a10184974c7c5"
+    ));
+
+    let mut keystream = [0; 64];
+    xchacha.set_counter(0);
+    xchacha.generate(&mut keystream);
+    assert_eq!(keystream[0], b'T' ^ 0x45);
+}
+
+fn decode(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+        .collect()
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "cipher")]
+
+extern crate rust_salsa20;
+use rust_salsa20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use rust_salsa20::{Salsa20, Salsa20_8, Salsa20_12, Key::Key32};
+
+const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+fn encrypt<C: KeyIvInit + StreamCipher>(
+    key: &[u8],
+    iv: &[u8],
+    buffer: &mut [u8]
+) {
+    let mut cipher = C::new_from_slices(key, iv).unwrap();
+    let (head, tail) = buffer.split_at_mut(buffer.len() / 3);
+    cipher.apply_keystream(head);
+    cipher.apply_keystream(tail);
+}
+
+fn encrypt_b2b<C: KeyIvInit + StreamCipher>(
+    key: &[u8],
+    iv: &[u8],
+    input: &[u8]
+) -> Vec<u8> {
+    let mut cipher = C::new_from_slices(key, iv).unwrap();
+    let mut output = vec![0; input.len()];
+    cipher.apply_keystream_b2b(input, &mut output).unwrap();
+    output
+}
+
+fn keystream_at<C: KeyIvInit + StreamCipher + StreamCipherSeek>(
+    key: &[u8],
+    iv: &[u8],
+    position: u64,
+    len: usize
+) -> Vec<u8> {
+    let mut cipher = C::new_from_slices(key, iv).unwrap();
+    cipher.seek(position);
+    assert_eq!(cipher.current_pos::<u64>(), position);
+
+    let mut buffer = vec![0; len];
+    cipher.apply_keystream(&mut buffer);
+    assert_eq!(cipher.current_pos::<u64>(), position + len as u64);
+    buffer
+}
+
+#[test]
+fn stream_cipher_test() {
+    let mut buffer = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0];
+    encrypt::<Salsa20>(&KEY, &NONCE, &mut buffer);
+    assert_eq!(buffer, [44, 132, 37, 162, 139, 34, 27, 154, 125, 157]);
+
+    let input = vec![7; 200];
+    let mut expected_data = input.clone();
+    Salsa20_12::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+
+    let mut buffer = input.clone();
+    encrypt::<Salsa20_12>(&KEY, &NONCE, &mut buffer);
+    assert_eq!(buffer, expected_data);
+    assert_eq!(encrypt_b2b::<Salsa20_12>(&KEY, &NONCE, &input), expected_data);
+
+    // longer than the keystream chunks, split off a block boundary
+    let input: Vec<u8> = (0..10_000).map(|index| index as u8).collect();
+    let mut expected_data = input.clone();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+
+    let mut buffer = input.clone();
+    encrypt::<Salsa20>(&KEY, &NONCE, &mut buffer);
+    assert_eq!(buffer, expected_data);
+    assert_eq!(encrypt_b2b::<Salsa20>(&KEY, &NONCE, &input), expected_data);
+}
+
+#[test]
+fn stream_cipher_seek_test() {
+    let mut expected_data = vec![0; 1024];
+    Salsa20_8::new(Key32(KEY), NONCE, 0).generate(&mut expected_data);
+
+    for &(position, len) in &[
+        (0, 1024), (1, 100), (63, 2), (64, 64), (100, 500), (999, 25)
+    ] {
+        let buffer = keystream_at::<Salsa20_8>(&KEY, &NONCE, position, len);
+        let position = position as usize;
+        assert_eq!(buffer[..], expected_data[position..position + len]);
+    }
+}
+
+fn position<C: StreamCipherSeek>(cipher: &C) -> u64 {
+    cipher.current_pos()
+}
+
+#[test]
+fn current_pos_test() {
+    let mut salsa = <Salsa20 as KeyIvInit>::new(&KEY.into(), &NONCE.into());
+    assert_eq!(position(&salsa), 0);
+
+    salsa.apply_keystream(&mut [0; 10]);
+    assert_eq!(position(&salsa), 10);
+    salsa.apply_keystream(&mut [0; 54]);
+    assert_eq!(position(&salsa), 64);
+    salsa.apply_keystream(&mut [0; 65]);
+    assert_eq!(position(&salsa), 129);
+
+    salsa.set_counter(3);
+    assert_eq!(position(&salsa), 192);
+
+    StreamCipherSeek::seek(&mut salsa, u64::MAX);
+    assert_eq!(position(&salsa), u64::MAX);
+    assert!(salsa.try_current_pos::<u32>().is_err());
+}
+
+#[test]
+fn keystream_end_test() {
+    let mut salsa = <Salsa20 as KeyIvInit>::new(&KEY.into(), &NONCE.into());
+    StreamCipherSeek::seek(&mut salsa, (1_u128 << 70) - 10);
+
+    let mut buffer = [0; 11];
+    assert!(salsa.try_apply_keystream(&mut buffer).is_err());
+    assert!(salsa.try_apply_keystream(&mut buffer[..10]).is_ok());
+    assert!(salsa.try_apply_keystream(&mut buffer[..1]).is_err());
+}
@@ -0,0 +1,291 @@
+#![cfg(feature = "cli")]
+
+extern crate rust_salsa20;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{self, Command, Output, Stdio};
+use std::thread;
+use rust_salsa20::{Salsa20, Salsa20_12, XSalsa20, Key::Key32};
+
+const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+const KEY_HEX: &str =
+    "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+const NONCE_HEX: &str = "0102030405060708";
+
+// a path in the temporary directory, unique to the test and the process
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("rust-salsa20-{}-{}", process::id(), name))
+}
+
+fn plaintext() -> Vec<u8> {
+    (0..100_000).map(|index| (index % 251) as u8).collect()
+}
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_salsa20"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // the input is written by another thread, so that the tool never waits
+    // for its full output to be read
+    let mut child_stdin = child.stdin.take().unwrap();
+    let stdin = stdin.to_vec();
+    let writer = thread::spawn(move || child_stdin.write_all(&stdin));
+    let output = child.wait_with_output().unwrap();
+    writer.join().unwrap().ok();
+    output
+}
+
+#[test]
+fn file_test() {
+    let plaintext_path = temp_path("file-plaintext");
+    let ciphertext_path = temp_path("file-ciphertext");
+    let decrypted_path = temp_path("file-decrypted");
+    fs::write(&plaintext_path, plaintext()).unwrap();
+
+    let output = run(&[
+        "encrypt", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        plaintext_path.to_str().unwrap(),
+        "-o", ciphertext_path.to_str().unwrap()
+    ], &[]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let mut expected_data = plaintext();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+    assert_eq!(fs::read(&ciphertext_path).unwrap(), expected_data);
+
+    let output = run(&[
+        "decrypt", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        ciphertext_path.to_str().unwrap(),
+        "--output", decrypted_path.to_str().unwrap()
+    ], &[]);
+    assert!(output.status.success());
+    assert_eq!(fs::read(&decrypted_path).unwrap(), plaintext());
+
+    for path in [plaintext_path, ciphertext_path, decrypted_path].iter() {
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn output_file_error_test() {
+    let plaintext_path = temp_path("error-plaintext");
+    let missing_path = temp_path("error-missing");
+    let output_path = temp_path("error-output");
+    fs::write(&plaintext_path, plaintext()).unwrap();
+
+    // the output is the input, which is kept
+    let plaintext_path_str = plaintext_path.to_str().unwrap();
+    let output = run(&[
+        "encrypt", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        plaintext_path_str, "-o", plaintext_path_str
+    ], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(fs::read(&plaintext_path).unwrap(), plaintext());
+
+    // a missing input creates no output
+    let output = run(&[
+        "encrypt", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        missing_path.to_str().unwrap(),
+        "-o", output_path.to_str().unwrap()
+    ], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!output_path.exists());
+
+    fs::remove_file(plaintext_path).unwrap();
+}
+
+#[test]
+fn stdin_key_file_test() {
+    let key_path = temp_path("stdin-key");
+    fs::write(&key_path, KEY).unwrap();
+    let key_path = key_path.to_str().unwrap();
+
+    let output = run(
+        &["encrypt", "--key-file", key_path, "--nonce", NONCE_HEX, "-"],
+        &plaintext()
+    );
+    assert!(output.status.success());
+
+    let mut expected_data = plaintext();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+
+    let output = run(
+        &["decrypt", "--key-file", key_path, "--nonce", NONCE_HEX],
+        &output.stdout
+    );
+    assert!(output.status.success());
+    assert_eq!(output.stdout, plaintext());
+
+    fs::write(key_path, &KEY[..20]).unwrap();
+    let output = run(
+        &["encrypt", "--key-file", key_path, "--nonce", NONCE_HEX],
+        &[]
+    );
+    assert_eq!(output.status.code(), Some(2));
+    fs::remove_file(key_path).unwrap();
+}
+
+#[test]
+fn stdin_key_test() {
+    let plaintext_path = temp_path("stdin-key-plaintext");
+    fs::write(&plaintext_path, plaintext()).unwrap();
+    let plaintext_path = plaintext_path.to_str().unwrap();
+
+    let output = run(
+        &["encrypt", "--key-file", "-", "--nonce", NONCE_HEX, plaintext_path],
+        &KEY
+    );
+    assert!(output.status.success());
+
+    let mut expected_data = plaintext();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+
+    let output = run(
+        &[
+            "keystream", "--key-file", "-", "--nonce", NONCE_HEX,
+            "--length", "100"
+        ],
+        &KEY
+    );
+    assert!(output.status.success());
+    let mut expected_data = [0; 100];
+    Salsa20::new(Key32(KEY), NONCE, 0).generate(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+
+    // the key and the input cannot both come from the standard input
+    let errors: [&[&str]; 2] = [
+        &["encrypt", "--key-file", "-", "--nonce", NONCE_HEX],
+        &["decrypt", "--key-file", "-", "--nonce", NONCE_HEX, "-"]
+    ];
+    for args in errors.iter() {
+        let output = run(args, &KEY);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+
+    let output = run(
+        &["encrypt", "--key-file", "-", "--nonce", NONCE_HEX, plaintext_path],
+        &KEY[..20]
+    );
+    assert_eq!(output.status.code(), Some(2));
+    fs::remove_file(plaintext_path).unwrap();
+}
+
+#[test]
+fn generated_nonce_test() {
+    let output = run(&["encrypt", "--key", KEY_HEX], &plaintext());
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let nonce_hex = stderr.trim().strip_prefix("nonce: ").unwrap();
+    assert_eq!(nonce_hex.len(), 16);
+
+    let decrypted = run(
+        &["decrypt", "--key", KEY_HEX, "--nonce", nonce_hex],
+        &output.stdout
+    );
+    assert!(decrypted.status.success());
+    assert_eq!(decrypted.stdout, plaintext());
+
+    let other = run(&["encrypt", "--key", KEY_HEX], &plaintext());
+    assert_ne!(other.stdout, output.stdout);
+}
+
+#[test]
+fn counter_offset_test() {
+    let output = run(&[
+        "encrypt", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        "--counter", "3", "--offset", "70"
+    ], &plaintext());
+    assert!(output.status.success());
+
+    let mut expected_data = plaintext();
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    salsa20.seek(3 * 64 + 70);
+    salsa20.encrypt(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+}
+
+#[test]
+fn variant_test() {
+    let nonce_hex = "0102030405060708090a0b0c0d0e0f101112131415161718";
+    let output = run(&[
+        "encrypt", "--key", KEY_HEX, "--nonce", nonce_hex,
+        "--variant", "xsalsa20"
+    ], &plaintext());
+    assert!(output.status.success());
+
+    let mut nonce = [0; 24];
+    nonce.iter_mut().zip(1..).for_each(|(byte, value)| *byte = value);
+    let mut expected_data = plaintext();
+    XSalsa20::new(Key32(KEY), nonce, 0).encrypt(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+
+    let output = run(&[
+        "encrypt", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        "--variant", "salsa20/12"
+    ], &plaintext());
+    assert!(output.status.success());
+
+    let mut expected_data = plaintext();
+    Salsa20_12::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+}
+
+#[test]
+fn keystream_test() {
+    let output = run(&[
+        "keystream", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        "--length", "100000", "--offset", "5"
+    ], &[]);
+    assert!(output.status.success());
+
+    let mut expected_data = vec![0; 100_000];
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    salsa20.seek(5);
+    salsa20.generate(&mut expected_data);
+    assert_eq!(output.stdout, expected_data);
+
+    let output = run(&[
+        "keystream", "--key", KEY_HEX, "--nonce", NONCE_HEX,
+        "--counter", "18446744073709551615", "--length", "65"
+    ], &[]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn usage_error_test() {
+    let errors: [&[&str]; 9] = [
+        &[],
+        &["compress"],
+        &["encrypt", "--nonce", NONCE_HEX],
+        &["decrypt", "--key", KEY_HEX],
+        &["encrypt", "--key", KEY_HEX, "--nonce", "0102"],
+        &["encrypt", "--key", KEY_HEX, "--variant", "chacha20"],
+        &["keystream", "--key", KEY_HEX, "--nonce", NONCE_HEX],
+        &["encrypt", "--key", KEY_HEX, "--counter", "-1"],
+        &["encrypt", "--key", KEY_HEX, "--length", "10"]
+    ];
+    for args in errors.iter() {
+        let output = run(args, &[]);
+        assert!(!output.status.success(), "{:?}", args);
+        assert!(output.stdout.is_empty(), "{:?}", args);
+        assert!(!output.stderr.is_empty(), "{:?}", args);
+    }
+
+    let output = run(&["--help"], &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().starts_with("usage"));
+}
@@ -0,0 +1,97 @@
+// fixtures shared by the io tests, each test crate uses a part of them
+#![allow(dead_code)]
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use rust_salsa20::{Salsa20, Key::Key32};
+
+pub const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+pub const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+pub fn salsa20() -> Salsa20 {
+    Salsa20::new(Key32(KEY), NONCE, 0)
+}
+
+pub fn plaintext(len: usize) -> Vec<u8> {
+    (0..len).map(|index| (index * 7 + 3) as u8).collect()
+}
+
+pub fn ciphertext(plaintext: &[u8]) -> Vec<u8> {
+    let mut buffer = plaintext.to_vec();
+    salsa20().encrypt(&mut buffer);
+    buffer
+}
+
+// is pending on every other call and accepts at most 5 bytes otherwise
+pub struct FlakyWriter {
+    pub data: Vec<u8>,
+    pub pending: bool
+}
+
+impl FlakyWriter {
+    pub fn new() -> FlakyWriter {
+        FlakyWriter { data: Vec::new(), pending: false }
+    }
+
+    fn poll_take(&mut self, cx: &mut Context<'_>, buf: &[u8])
+        -> Poll<io::Result<usize>>
+    {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let len = buf.len().min(5);
+        self.data.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl tokio::io::AsyncWrite for FlakyWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_take(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>)
+        -> Poll<io::Result<()>>
+    {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>)
+        -> Poll<io::Result<()>>
+    {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl futures::io::AsyncWrite for FlakyWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_take(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>)
+        -> Poll<io::Result<()>>
+    {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>)
+        -> Poll<io::Result<()>>
+    {
+        Poll::Ready(Ok(()))
+    }
+}
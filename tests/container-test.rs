@@ -0,0 +1,221 @@
+#![cfg(feature = "std")]
+
+extern crate rust_salsa20;
+use rust_salsa20::Key::{self, Key16, Key32};
+use rust_salsa20::container::*;
+use rust_salsa20::{Salsa20, XSalsa20};
+
+const KEY: Key = Key32([7; 32]);
+
+fn plaintext(len: usize) -> Vec<u8> {
+    (0..len).map(|index| (index % 251) as u8).collect()
+}
+
+fn seal(key: &Key, header: &Header, plaintext: &[u8]) -> Vec<u8> {
+    let mut sealed = Vec::new();
+    let len = seal_file(key, header, plaintext, &mut sealed).unwrap();
+    assert_eq!(len, plaintext.len() as u64);
+    sealed
+}
+
+fn open(key: &Key, sealed: &[u8]) -> Result<Vec<u8>, ContainerError> {
+    let mut opened = Vec::new();
+    open_file(key, sealed, &mut opened)?;
+    Ok(opened)
+}
+
+// a xorshift generator, the tests only need reproducible noise
+fn noise(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn round_trip_test() {
+    let variants = [Variant::Salsa20([1; 8]), Variant::XSalsa20([2; 24])];
+    for &variant in variants.iter() {
+        for &key in [KEY, Key16([3; 16])].iter() {
+            for &chunk_size in [1, 64, 100].iter() {
+                for &len in [0, 1, 63, 64, 99, 100, 101, 200, 1000].iter() {
+                    test(key, Header { variant, chunk_size }, len);
+                }
+            }
+        }
+        test(KEY, Header::new(variant), 200_000);
+    }
+
+    fn test(key: Key, header: Header, len: usize) {
+        let sealed = seal(&key, &header, &plaintext(len));
+        let mut opened = Vec::new();
+        assert_eq!(open_file(&key, &sealed[..], &mut opened).unwrap(), header);
+        assert_eq!(opened, plaintext(len));
+    }
+}
+
+#[test]
+fn layout_test() {
+    let header = Header { variant: Variant::Salsa20([1; 8]), chunk_size: 100 };
+    let sealed = seal(&KEY, &header, &plaintext(150));
+
+    let mut expected_header = b"SALSA20F\x01\x01\x64\x00\x00\x00".to_vec();
+    expected_header.extend_from_slice(&[1; 8]);
+    assert_eq!(sealed[..22], expected_header[..]);
+    assert_eq!(sealed.len(), 22 + 16 + 100 + 16 + 50 + 16);
+
+    // the chunks are encrypted from the second block of their range, a
+    // chunk of 100 bytes takes 1 + 2 blocks after the block of the header
+    let mut expected_data = plaintext(150);
+    let mut salsa20 = Salsa20::new(KEY, [1; 8], 2);
+    salsa20.encrypt(&mut expected_data[..100]);
+    salsa20.set_counter(5);
+    salsa20.encrypt(&mut expected_data[100..]);
+    assert_eq!(sealed[38..138], expected_data[..100]);
+    assert_eq!(sealed[154..204], expected_data[100..]);
+
+    let header = Header::new(Variant::XSalsa20([2; 24]));
+    let sealed = seal(&KEY, &header, &plaintext(10));
+    let mut expected_data = plaintext(10);
+    XSalsa20::new(KEY, [2; 24], 2).encrypt(&mut expected_data);
+    assert_eq!(sealed[..10], b"SALSA20F\x01\x02"[..]);
+    assert_eq!(sealed[54..64], expected_data[..]);
+}
+
+#[test]
+fn tamper_test() {
+    let header = Header { variant: Variant::XSalsa20([4; 24]), chunk_size: 32 };
+    let sealed = seal(&KEY, &header, &plaintext(100));
+    let header_len = 14 + 24 + 16;
+
+    for index in 0..sealed.len() {
+        for &bit in [0x01, 0x80].iter() {
+            let mut tampered = sealed.clone();
+            tampered[index] ^= bit;
+
+            let mut opened = Vec::new();
+            assert!(open_file(&KEY, &tampered[..], &mut opened).is_err());
+            if index < header_len {
+                assert!(opened.is_empty());
+            }
+        }
+    }
+
+    let error = open(&Key32([8; 32]), &sealed).unwrap_err();
+    assert!(matches!(error, ContainerError::AuthenticationFailed));
+}
+
+#[test]
+fn truncation_test() {
+    let header = Header { variant: Variant::Salsa20([5; 8]), chunk_size: 32 };
+    for &len in [0, 31, 32, 64, 100].iter() {
+        let sealed = seal(&KEY, &header, &plaintext(len));
+        for cut in 0..sealed.len() {
+            assert!(open(&KEY, &sealed[..cut]).is_err(), "{} {}", len, cut);
+        }
+
+        let mut extended = sealed.clone();
+        extended.push(0);
+        assert!(open(&KEY, &extended).is_err());
+    }
+
+    // cut at the end of a full chunk, the empty last chunk is missing
+    let sealed = seal(&KEY, &header, &plaintext(64));
+    let cut = 22 + 16 + 2 * (32 + 16);
+    let error = open(&KEY, &sealed[..cut]).unwrap_err();
+    assert!(matches!(error, ContainerError::Truncated));
+}
+
+#[test]
+fn reorder_test() {
+    let header = Header { variant: Variant::Salsa20([6; 8]), chunk_size: 16 };
+    let sealed = seal(&KEY, &header, &plaintext(40));
+
+    let chunks_start = 22 + 16;
+    let mut reordered = sealed.clone();
+    reordered[chunks_start..chunks_start + 32]
+        .copy_from_slice(&sealed[chunks_start + 32..chunks_start + 64]);
+    reordered[chunks_start + 32..chunks_start + 64]
+        .copy_from_slice(&sealed[chunks_start..chunks_start + 32]);
+    let error = open(&KEY, &reordered).unwrap_err();
+    assert!(matches!(error, ContainerError::AuthenticationFailed));
+}
+
+#[test]
+fn header_errors_test() {
+    let header = Header { variant: Variant::Salsa20([1; 8]), chunk_size: 64 };
+    let sealed = seal(&KEY, &header, &plaintext(10));
+
+    let mut other = sealed.clone();
+    other[0] = b'X';
+    assert!(matches!(open(&KEY, &other), Err(ContainerError::InvalidHeader)));
+
+    let mut other = sealed.clone();
+    other[8] = 2;
+    let error = open(&KEY, &other).unwrap_err();
+    assert!(matches!(error, ContainerError::UnsupportedVersion(2)));
+
+    let mut other = sealed.clone();
+    other[9] = 3;
+    assert!(matches!(open(&KEY, &other), Err(ContainerError::InvalidHeader)));
+
+    for &chunk_size in [0, MAX_CHUNK_SIZE + 1, u32::MAX].iter() {
+        let mut other = sealed.clone();
+        other[10..14].copy_from_slice(&chunk_size.to_le_bytes());
+        let error = open(&KEY, &other).unwrap_err();
+        assert!(matches!(error, ContainerError::InvalidHeader));
+
+        let header = Header { chunk_size, ..header };
+        let error = seal_file(&KEY, &header, &[][..], Vec::new()).unwrap_err();
+        assert!(matches!(error, ContainerError::InvalidHeader));
+    }
+
+    // a changed variant reads a nonce of another length at another place
+    let mut other = sealed.clone();
+    other[9] = 2;
+    assert!(open(&KEY, &other).is_err());
+}
+
+#[test]
+fn corrupted_header_fuzz_test() {
+    let header = Header { variant: Variant::XSalsa20([9; 24]), chunk_size: 50 };
+    let sealed = seal(&KEY, &header, &plaintext(120));
+    let header_len = 14 + 24 + 16;
+
+    let mut state = 0x0123_4567_89ab_cdef;
+    for _ in 0..5000 {
+        let mut corrupted = sealed.clone();
+        let changes = 1 + noise(&mut state) % 4;
+        for _ in 0..changes {
+            let index = (noise(&mut state) % header_len as u64) as usize;
+            let value = noise(&mut state) as u8;
+            corrupted[index] = value;
+        }
+        if corrupted == sealed {
+            continue;
+        }
+
+        let mut opened = Vec::new();
+        assert!(open_file(&KEY, &corrupted[..], &mut opened).is_err());
+        assert!(opened.is_empty());
+    }
+
+    for _ in 0..1000 {
+        let len = (noise(&mut state) % 128) as usize;
+        let mut random: Vec<u8> = (0..len)
+            .map(|_| noise(&mut state) as u8)
+            .collect();
+        if len >= 10 && noise(&mut state) & 1 == 0 {
+            random[..10].copy_from_slice(b"SALSA20F\x01\x01");
+        }
+        assert!(open(&KEY, &random).is_err());
+    }
+}
+
+#[test]
+fn display_test() {
+    let error = ContainerError::UnsupportedVersion(3);
+    assert_eq!(error.to_string(), "unsupported container version 3");
+    let error = ContainerError::AuthenticationFailed;
+    assert_eq!(error.to_string(), "container authentication failed");
+}
@@ -0,0 +1,102 @@
+extern crate rust_salsa20;
+use rust_salsa20::{CounterOverflow, Salsa20, XSalsa20, Key::Key32};
+
+const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+#[test]
+fn try_generate_test() {
+    let mut expected_data = [0; 64];
+    Salsa20::new(Key32(KEY), NONCE, u64::MAX).generate(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, u64::MAX);
+    let mut buffer = [0; 65];
+    assert_eq!(salsa20.try_generate(&mut buffer), Err(CounterOverflow));
+    assert_eq!(buffer[..], [0; 65][..]);
+
+    let mut buffer = [0; 64];
+    assert_eq!(salsa20.try_generate(&mut buffer[..10]), Ok(()));
+    assert_eq!(salsa20.try_generate(&mut buffer[10..]), Ok(()));
+    assert_eq!(buffer, expected_data);
+
+    assert_eq!(salsa20.current_pos(), 1 << 70);
+    assert_eq!(salsa20.try_generate(&mut []), Ok(()));
+    assert_eq!(salsa20.try_generate(&mut [0]), Err(CounterOverflow));
+}
+
+#[test]
+fn try_encrypt_test() {
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, u64::MAX - 1);
+    let mut buffer = [1; 200];
+    assert_eq!(salsa20.try_encrypt(&mut buffer), Err(CounterOverflow));
+    assert_eq!(buffer[..], [1; 200][..]);
+
+    assert_eq!(salsa20.try_encrypt(&mut buffer[..128]), Ok(()));
+    assert_eq!(salsa20.try_encrypt(&mut buffer[128..]), Err(CounterOverflow));
+
+    salsa20.set_counter(0);
+    assert_eq!(salsa20.try_encrypt(&mut buffer), Ok(()));
+}
+
+#[test]
+fn wrapping_test() {
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, u64::MAX);
+    salsa20.generate(&mut [0; 100]);
+    assert_eq!(salsa20.current_pos(), (1 << 70) + 36);
+    assert_eq!(salsa20.try_generate(&mut []), Err(CounterOverflow));
+
+    salsa20.seek(36);
+    assert_eq!(salsa20.try_generate(&mut [0; 100]), Ok(()));
+}
+
+#[test]
+fn strict_test() {
+    let mut salsa20 = Salsa20::new_strict(Key32(KEY), NONCE, u64::MAX);
+    let mut buffer = [0; 64];
+    salsa20.encrypt(&mut buffer[..32]);
+    salsa20.generate(&mut buffer[32..]);
+    assert_eq!(salsa20.try_encrypt(&mut [0]), Err(CounterOverflow));
+}
+
+#[test]
+#[should_panic(expected = "counter overflow")]
+fn strict_encrypt_test() {
+    let mut salsa20 = Salsa20::new_strict(Key32(KEY), NONCE, u64::MAX);
+    salsa20.encrypt(&mut [0; 65]);
+}
+
+#[test]
+#[should_panic(expected = "counter overflow")]
+fn strict_generate_test() {
+    let mut salsa20 = Salsa20::new_strict(Key32(KEY), NONCE, u64::MAX);
+    salsa20.generate(&mut [0; 63]);
+    salsa20.generate(&mut [0; 2]);
+}
+
+#[test]
+#[should_panic(expected = "counter overflow")]
+fn strict_seek_test() {
+    let mut salsa20 = Salsa20::new_strict(Key32(KEY), NONCE, 0);
+    salsa20.seek((1 << 70) - 1);
+    salsa20.encrypt(&mut [0; 2]);
+}
+
+#[test]
+fn xsalsa20_test() {
+    let nonce = [3; 24];
+    let mut xsalsa20 = XSalsa20::new(Key32(KEY), nonce, u64::MAX);
+    assert_eq!(xsalsa20.try_encrypt(&mut [0; 65]), Err(CounterOverflow));
+    assert_eq!(xsalsa20.try_generate(&mut [0; 64]), Ok(()));
+    assert_eq!(xsalsa20.try_generate(&mut [0; 1]), Err(CounterOverflow));
+}
+
+#[test]
+#[should_panic(expected = "counter overflow")]
+fn xsalsa20_strict_test() {
+    let nonce = [3; 24];
+    let mut xsalsa20 = XSalsa20::new_strict(Key32(KEY), nonce, u64::MAX);
+    xsalsa20.encrypt(&mut [0; 65]);
+}
@@ -0,0 +1,69 @@
+extern crate rust_salsa20;
+use rust_salsa20::poly1305::Poly1305;
+use rust_salsa20::{ChaCha20, Salsa20, XSalsa20, Key::{Key16, Key32}};
+
+const KEY: [u8; 32] = [
+    0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7, 0xa8, 0xa9, 0xaa, 0xab,
+    0xac, 0xad, 0xae, 0xaf, 0xb0, 0xb1, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6,
+    0xb7, 0xb8, 0xb9, 0xba, 0xbb, 0xbc, 0xbd, 0xbe, 0xbf, 0xc0
+];
+
+#[test]
+fn key_debug_test() {
+    assert_eq!(format!("{:?}", Key32(KEY)), "Key32 { len: 32, .. }");
+    assert_eq!(format!("{:?}", Key16([7; 16])), "Key16 { len: 16, .. }");
+
+    assert_eq!(
+        format!("{:?}", Key16([7; 16]).dangerous_debug()),
+        format!("Key16({:?})", [7; 16])
+    );
+    assert_eq!(
+        format!("{:?}", Key32(KEY).dangerous_debug()),
+        format!("Key32({:?})", KEY)
+    );
+}
+
+#[test]
+fn salsa20_debug_test() {
+    let mut salsa20 = Salsa20::new(Key32(KEY), [1; 8], 0);
+    salsa20.generate(&mut [0; 70]);
+    assert_eq!(
+        format!("{:?}", salsa20),
+        "Salsa { generator: Generator { counter: 2, wrapped: false, .. }, \
+            overflow: Overflow { offset: 6, .. }, strict: false }"
+    );
+
+    let mut keystream = [0; 128];
+    Salsa20::new(Key32(KEY), [1; 8], 0).generate(&mut keystream);
+    let output = format!("{:?}", salsa20.dangerous_debug());
+    assert!(output.starts_with("Salsa { init_matrix: ["));
+    assert!(output.contains("counter: 2, wrapped: false"));
+    assert!(output.contains(&format!("{:?}", &keystream[70..])));
+}
+
+#[test]
+fn redacted_debug_test() {
+    // decimal forms of the key bytes and of a key word
+    let secrets = ["161", "162", "192", &0xa4a3a2a1_u32.to_string()];
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), [1; 8], 0);
+    salsa20.generate(&mut [0; 10]);
+    let mut xsalsa20 = XSalsa20::new(Key32(KEY), [1; 24], 0);
+    xsalsa20.generate(&mut [0; 10]);
+    let mut chacha20 = ChaCha20::new(Key32(KEY), [1; 8], 0);
+    chacha20.generate(&mut [0; 10]);
+    let mut poly1305 = Poly1305::new(&KEY);
+    poly1305.update(&KEY[..5]);
+
+    for output in &[
+        format!("{:?}", Key32(KEY)),
+        format!("{:?}", salsa20),
+        format!("{:?}", xsalsa20),
+        format!("{:?}", chacha20),
+        format!("{:?}", poly1305)
+    ] {
+        for secret in &secrets {
+            assert!(!output.contains(secret), "{}", output);
+        }
+    }
+}
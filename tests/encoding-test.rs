@@ -0,0 +1,119 @@
+extern crate rust_salsa20;
+use std::convert::TryFrom;
+use rust_salsa20::{Error, Key, Nonce, Salsa20, XSalsa20};
+
+const KEY_HEX: &str =
+    "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const KEY_BASE64: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+
+fn key_bytes(key: Key) -> Vec<u8> {
+    match key {
+        Key::Key16(key) => key.to_vec(),
+        Key::Key32(key) => key.to_vec()
+    }
+}
+
+#[test]
+fn key_from_hex_test() {
+    let key = Key::from_hex(KEY_HEX).unwrap();
+    assert_eq!(key_bytes(key), (0..32).collect::<Vec<u8>>());
+
+    let key = Key::from_hex(&KEY_HEX.to_uppercase()).unwrap();
+    assert_eq!(key_bytes(key), (0..32).collect::<Vec<u8>>());
+
+    let key = Key::from_hex(&KEY_HEX[..32]).unwrap();
+    assert_eq!(key_bytes(key), (0..16).collect::<Vec<u8>>());
+
+    test(&KEY_HEX[..62], Error::InvalidKeyLength);
+    test(&[KEY_HEX, "00"].concat(), Error::InvalidKeyLength);
+    test("", Error::InvalidKeyLength);
+    test(&KEY_HEX[..63], Error::InvalidEncoding);
+    test(&KEY_HEX.replace('f', "g"), Error::InvalidEncoding);
+
+    fn test(key: &str, error: Error) {
+        assert_eq!(Key::from_hex(key).map(key_bytes), Err(error));
+    }
+}
+
+#[test]
+fn key_from_base64_test() {
+    let key = Key::from_base64(KEY_BASE64).unwrap();
+    assert_eq!(key_bytes(key), (0..32).collect::<Vec<u8>>());
+
+    let key = Key::from_base64("AAECAwQFBgcICQoLDA0ODw==").unwrap();
+    assert_eq!(key_bytes(key), (0..16).collect::<Vec<u8>>());
+
+    test("AAECAwQFBgcICQoLDA0O", Error::InvalidKeyLength);
+    test(&[KEY_BASE64, "AAAA"].concat(), Error::InvalidKeyLength);
+    test(&KEY_BASE64[..43], Error::InvalidEncoding);
+    test(&KEY_BASE64.replace('A', "-"), Error::InvalidEncoding);
+
+    fn test(key: &str, error: Error) {
+        assert_eq!(Key::from_base64(key).map(key_bytes), Err(error));
+    }
+}
+
+#[test]
+fn key_encode_test() {
+    let key = Key::from_hex(KEY_HEX).unwrap();
+
+    let mut buffer = [0; 64];
+    assert_eq!(key.to_hex(&mut buffer), Ok(KEY_HEX));
+    assert_eq!(key.to_hex(&mut buffer[..63]), Err(Error::BufferTooSmall));
+
+    let mut buffer = [0; 50];
+    assert_eq!(key.to_base64(&mut buffer), Ok(KEY_BASE64));
+    assert_eq!(key.to_base64(&mut buffer[..43]), Err(Error::BufferTooSmall));
+
+    let key = Key::Key16([0xff; 16]);
+    let mut buffer = [0; 32];
+    assert_eq!(key.to_hex(&mut buffer), Ok("ff".repeat(16).as_str()));
+    assert_eq!(
+        key.to_base64(&mut buffer),
+        Ok("/////////////////////w==")
+    );
+}
+
+#[test]
+fn nonce_test() {
+    let nonce = Nonce::<8>::from_hex("0102030405060708").unwrap();
+    assert_eq!(nonce, Nonce([1, 2, 3, 4, 5, 6, 7, 8]));
+    assert_eq!(Nonce::<8>::from_base64("AQIDBAUGBwg="), Ok(nonce));
+    assert_eq!(Nonce::<8>::from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]), Ok(nonce));
+    assert_eq!(Nonce::<8>::try_from(&nonce.0[..]), Ok(nonce));
+
+    let mut buffer = [0; 16];
+    assert_eq!(nonce.to_hex(&mut buffer), Ok("0102030405060708"));
+    assert_eq!(nonce.to_base64(&mut buffer), Ok("AQIDBAUGBwg="));
+    assert_eq!(nonce.to_hex(&mut buffer[..15]), Err(Error::BufferTooSmall));
+
+    let error = Err(Error::InvalidNonceLength);
+    assert_eq!(Nonce::<8>::from_hex("01020304050607"), error);
+    assert_eq!(Nonce::<8>::from_hex("010203040506070809"), error);
+    assert_eq!(Nonce::<8>::from_base64("AQIDBAUGBw=="), error);
+    assert_eq!(Nonce::<8>::from_slice(&[0; 24]), error);
+    assert_eq!(
+        Nonce::<24>::from_hex("0102030405060708"),
+        Err(Error::InvalidNonceLength)
+    );
+    assert_eq!(
+        Nonce::<8>::from_hex("01020304050607xx"),
+        Err(Error::InvalidEncoding)
+    );
+}
+
+#[test]
+fn cipher_test() {
+    let key = Key::from_hex(KEY_HEX).unwrap();
+    let nonce = Nonce::<8>::from_hex("0102030405060708").unwrap();
+    let mut salsa20 = Salsa20::new(key, nonce.into(), 0);
+    let mut buffer = [0; 10];
+    salsa20.generate(&mut buffer);
+    assert_eq!(buffer, [45, 134, 38, 166, 142, 36, 28, 146, 116, 157]);
+
+    let nonce = Nonce::<24>::from_base64(&"AAAA".repeat(8)).unwrap();
+    let mut expected_data = [0; 10];
+    XSalsa20::new(key, [0; 24], 0).generate(&mut expected_data);
+    XSalsa20::new(key, nonce.into(), 0).generate(&mut buffer);
+    assert_eq!(buffer, expected_data);
+}
@@ -0,0 +1,93 @@
+extern crate rust_salsa20;
+use rust_salsa20::{LengthMismatch, Salsa20, XSalsa20, Key::Key32};
+
+const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+fn input(len: usize) -> Vec<u8> {
+    (0..len).map(|index| (index * 7 + 3) as u8).collect()
+}
+
+#[test]
+fn encrypt_b2b_test() {
+    let input = [1, 2, 3, 4, 5, 6, 7, 8, 9, 0];
+    let mut output = [0; 10];
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    assert_eq!(salsa20.encrypt_b2b(&input, &mut output), Ok(()));
+    assert_eq!(output, [44, 132, 37, 162, 139, 34, 27, 154, 125, 157]);
+}
+
+#[test]
+fn encrypt_b2b_split_test() {
+    let input = input(1500);
+    let mut expected_data = input.clone();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+
+    test(&input, &expected_data, &[1500]);
+    test(&input, &expected_data, &[1, 1499]);
+    test(&input, &expected_data, &[63, 2, 63, 1372]);
+    test(&input, &expected_data, &[7, 252, 679, 86, 0, 476]);
+    test(&input, &expected_data, &[64, 128, 256, 512, 540]);
+    test(&input, &expected_data, &[300, 3, 600, 597]);
+
+    fn test(input: &[u8], expected_data: &[u8], splits: &[usize]) {
+        let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+        let mut output = vec![0; input.len()];
+        let mut offset = 0;
+        for &len in splits {
+            let range = offset..offset + len;
+            salsa20
+                .encrypt_b2b(&input[range.clone()], &mut output[range])
+                .unwrap();
+            offset += len;
+        }
+        assert_eq!(output, expected_data);
+    }
+}
+
+#[test]
+fn encrypt_b2b_mixed_test() {
+    let input = input(300);
+    let mut expected_data = input.clone();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    let mut output = input.clone();
+    salsa20.encrypt(&mut output[..10]);
+    salsa20.encrypt_b2b(&input[10..200], &mut output[10..200]).unwrap();
+    salsa20.encrypt(&mut output[200..]);
+    assert_eq!(output, expected_data);
+}
+
+#[test]
+fn encrypt_b2b_length_mismatch_test() {
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    let mut output = [0; 10];
+    assert_eq!(
+        salsa20.encrypt_b2b(&[1; 11], &mut output),
+        Err(LengthMismatch)
+    );
+    assert_eq!(output, [0; 10]);
+    assert_eq!(salsa20.current_pos(), 0);
+}
+
+#[test]
+fn xsalsa20_encrypt_b2b_test() {
+    let nonce = [3; 24];
+    let input = input(200);
+    let mut expected_data = input.clone();
+    XSalsa20::new(Key32(KEY), nonce, 0).encrypt(&mut expected_data);
+
+    let mut xsalsa20 = XSalsa20::new(Key32(KEY), nonce, 0);
+    let mut output = vec![0; 200];
+    xsalsa20.encrypt_b2b(&input[..99], &mut output[..99]).unwrap();
+    xsalsa20.encrypt_b2b(&input[99..], &mut output[99..]).unwrap();
+    assert_eq!(output, expected_data);
+    assert_eq!(
+        xsalsa20.encrypt_b2b(&input, &mut output[1..]),
+        Err(LengthMismatch)
+    );
+}
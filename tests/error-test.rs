@@ -0,0 +1,86 @@
+extern crate rust_salsa20;
+use std::convert::TryFrom;
+use rust_salsa20::{CounterOverflow, Error, Key, Salsa20, XSalsa20};
+
+#[test]
+fn key_from_slice_test() {
+    test(&[1; 16], Ok([1; 16].to_vec()));
+    test(&[2; 32], Ok([2; 32].to_vec()));
+    test(&[], Err(Error::InvalidKeyLength));
+    test(&[3; 15], Err(Error::InvalidKeyLength));
+    test(&[4; 24], Err(Error::InvalidKeyLength));
+    test(&[5; 33], Err(Error::InvalidKeyLength));
+
+    fn test(key: &[u8], expected: Result<Vec<u8>, Error>) {
+        let bytes = |key: Key| match key {
+            Key::Key16(key) => key.to_vec(),
+            Key::Key32(key) => key.to_vec()
+        };
+        assert_eq!(Key::from_slice(key).map(bytes), expected);
+        assert_eq!(Key::try_from(key).map(bytes), expected);
+    }
+}
+
+#[test]
+fn salsa20_new_from_slices_test() {
+    let key = [1; 32];
+    let nonce = [2; 8];
+    let mut expected_data = [0; 100];
+    Salsa20::new(Key::Key32(key), nonce, 3).generate(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new_from_slices(&key, &nonce, 3).unwrap();
+    let mut buffer = [0; 100];
+    salsa20.generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[..]);
+
+    let error = Salsa20::new_from_slices(&key[..31], &nonce, 0).unwrap_err();
+    assert_eq!(error, Error::InvalidKeyLength);
+    let error = Salsa20::new_from_slices(&key, &[2; 24], 0).unwrap_err();
+    assert_eq!(error, Error::InvalidNonceLength);
+}
+
+#[test]
+fn xsalsa20_new_from_slices_test() {
+    let key = [1; 32];
+    let nonce = [2; 24];
+    let mut expected_data = [0; 100];
+    XSalsa20::new(Key::Key32(key), nonce, 3).generate(&mut expected_data);
+
+    let mut xsalsa20 = XSalsa20::new_from_slices(&key, &nonce, 3).unwrap();
+    let mut buffer = [0; 100];
+    xsalsa20.generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[..]);
+
+    let error = XSalsa20::new_from_slices(&key[..16], &[2; 8], 0).unwrap_err();
+    assert_eq!(error, Error::InvalidNonceLength);
+    let error = XSalsa20::new_from_slices(&[1; 20], &nonce, 0).unwrap_err();
+    assert_eq!(error, Error::InvalidKeyLength);
+}
+
+#[test]
+fn counter_overflow_into_error_test() {
+    fn generate(salsa20: &mut Salsa20, buffer: &mut [u8]) -> Result<(), Error> {
+        salsa20.try_generate(buffer)?;
+        Ok(())
+    }
+
+    let mut salsa20 = Salsa20::new_from_slices(&[1; 16], &[2; 8], u64::MAX)
+        .unwrap();
+    assert_eq!(generate(&mut salsa20, &mut [0; 64]), Ok(()));
+    assert_eq!(generate(&mut salsa20, &mut [0]), Err(Error::CounterOverflow));
+    assert_eq!(Error::from(CounterOverflow), Error::CounterOverflow);
+}
+
+#[test]
+fn display_test() {
+    assert_eq!(Error::InvalidKeyLength.to_string(), "invalid key length");
+    assert_eq!(Error::InvalidNonceLength.to_string(), "invalid nonce length");
+    assert_eq!(Error::CounterOverflow.to_string(), "salsa20 counter overflow");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn std_error_test() {
+    let error: Box<dyn std::error::Error> = Box::new(Error::InvalidKeyLength);
+    assert_eq!(error.to_string(), "invalid key length");
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "futures-io")]
+
+extern crate rust_salsa20;
+use futures::executor::block_on;
+use futures::future::join;
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+use rust_salsa20::io::futures::{DecryptingReader, EncryptingWriter};
+
+mod common;
+use common::{ciphertext, plaintext, salsa20, FlakyWriter};
+
+async fn read_all<R: AsyncRead + Unpin>(reader: &mut R) -> Vec<u8> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).await.unwrap();
+    data
+}
+
+#[test]
+fn duplex_test() {
+    let plaintext = plaintext(10000);
+    let (client, server) = tokio::io::duplex(64);
+    let mut writer = EncryptingWriter::new(salsa20(), client.compat());
+    let mut reader = DecryptingReader::new(salsa20(), server.compat());
+
+    let write = async {
+        writer.write_all(&plaintext[..3333]).await.unwrap();
+        writer.write_all(&plaintext[3333..]).await.unwrap();
+        writer.close().await.unwrap();
+    };
+    let (_, data) = block_on(join(write, read_all(&mut reader)));
+    assert_eq!(data, plaintext);
+}
+
+#[test]
+fn duplex_ciphertext_test() {
+    let plaintext = plaintext(5000);
+    let (client, server) = tokio::io::duplex(100);
+    let mut writer = EncryptingWriter::new(salsa20(), client.compat());
+    let mut server = server.compat();
+
+    let write = async {
+        for chunk in plaintext.chunks(77) {
+            writer.write_all(chunk).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+        writer.close().await.unwrap();
+    };
+    let (_, data) = block_on(join(write, read_all(&mut server)));
+    assert_eq!(data, ciphertext(&plaintext));
+}
+
+#[test]
+fn partial_write_test() {
+    let plaintext = plaintext(1000);
+    let inner = FlakyWriter::new();
+    let mut writer = EncryptingWriter::new(salsa20(), inner);
+    block_on(writer.write_all(&plaintext)).unwrap();
+    assert_eq!(writer.into_inner().data, ciphertext(&plaintext));
+}
+
+#[test]
+fn small_reads_test() {
+    let plaintext = plaintext(1000);
+    let ciphertext = ciphertext(&plaintext);
+    let mut reader = DecryptingReader::new(salsa20(), &ciphertext[..]);
+
+    let mut data = Vec::new();
+    let mut buffer = [0; 13];
+    loop {
+        let len = block_on(reader.read(&mut buffer)).unwrap();
+        if len == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..len]);
+    }
+    assert_eq!(data, plaintext);
+}
@@ -0,0 +1,105 @@
+extern crate rust_salsa20;
+use rust_salsa20::{hsalsa20, Salsa20, Key, Key::{Key16, Key32}};
+
+#[test]
+fn hsalsa20_test() {
+    test(
+        Key32([
+            0x4a, 0x5d, 0x9d, 0x5b, 0xa4, 0xce, 0x2d, 0xe1, 0x72, 0x8e, 0x3b,
+            0xf4, 0x80, 0x35, 0x0f, 0x25, 0xe0, 0x7e, 0x21, 0xc9, 0x47, 0xd1,
+            0x9e, 0x33, 0x76, 0xf0, 0x9b, 0x3c, 0x1e, 0x16, 0x17, 0x42
+        ]),
+        [0; 16],
+        [
+            0x1b, 0x27, 0x55, 0x64, 0x73, 0xe9, 0x85, 0xd4, 0x62, 0xcd, 0x51,
+            0x19, 0x7a, 0x9a, 0x46, 0xc7, 0x60, 0x09, 0x54, 0x9e, 0xac, 0x64,
+            0x74, 0xf2, 0x06, 0xc4, 0xee, 0x08, 0x44, 0xf6, 0x83, 0x89
+        ]
+    );
+
+    test(
+        Key32([
+            0x1b, 0x27, 0x55, 0x64, 0x73, 0xe9, 0x85, 0xd4, 0x62, 0xcd, 0x51,
+            0x19, 0x7a, 0x9a, 0x46, 0xc7, 0x60, 0x09, 0x54, 0x9e, 0xac, 0x64,
+            0x74, 0xf2, 0x06, 0xc4, 0xee, 0x08, 0x44, 0xf6, 0x83, 0x89
+        ]),
+        [
+            0x69, 0x69, 0x6e, 0xe9, 0x55, 0xb6, 0x2b, 0x73, 0xcd, 0x62, 0xbd,
+            0xa8, 0x75, 0xfc, 0x73, 0xd6
+        ],
+        [
+            0xdc, 0x90, 0x8d, 0xda, 0x0b, 0x93, 0x44, 0xa9, 0x53, 0x62, 0x9b,
+            0x73, 0x38, 0x20, 0x77, 0x88, 0x80, 0xf3, 0xce, 0xb4, 0x21, 0xbb,
+            0x61, 0xb9, 0x1c, 0xbd, 0x4c, 0x3e, 0x66, 0x25, 0x6c, 0xe4
+        ]
+    );
+
+    fn test(key: Key, input: [u8; 16], expected_data: [u8; 32]) {
+        assert_eq!(hsalsa20(&key, &input), expected_data);
+    }
+}
+
+#[test]
+fn hsalsa20_matches_salsa20_block_test() {
+    test(Key16([
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16
+    ]), [
+        0x65, 0x78, 0x70, 0x61, 0x6e, 0x64, 0x20, 0x31,
+        0x36, 0x2d, 0x62, 0x79, 0x74, 0x65, 0x20, 0x6b
+    ]);
+
+    test(Key32([
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 201, 202,
+        203, 204, 205, 206, 207, 208, 209, 210, 211, 212, 213, 214, 215, 216
+    ]), [
+        0x65, 0x78, 0x70, 0x61, 0x6e, 0x64, 0x20, 0x33,
+        0x32, 0x2d, 0x62, 0x79, 0x74, 0x65, 0x20, 0x6b
+    ]);
+
+    // HSalsa20 is the Salsa20 block without the final addition of the
+    // input words, restricted to the diagonal and the nonce/counter words
+    fn test(key: Key, constants: [u8; 16]) {
+        let input = [
+            101, 102, 103, 104, 105, 106, 107, 108,
+            109, 110, 111, 112, 113, 114, 115, 116
+        ];
+        let mut nonce = [0; 8];
+        let mut counter = [0; 8];
+        nonce.copy_from_slice(&input[..8]);
+        counter.copy_from_slice(&input[8..]);
+
+        let mut block = [0; 64];
+        Salsa20::new(key, nonce, u64::from_le_bytes(counter))
+            .generate(&mut block);
+
+        let init_words = [
+            &constants[..4], &constants[4..8], &constants[8..12],
+            &constants[12..], &input[..4], &input[4..8], &input[8..12],
+            &input[12..]
+        ];
+        let positions = [0, 5, 10, 15, 6, 7, 8, 9];
+
+        let mut expected_data = [0; 32];
+        for (index, (&position, init_word)) in positions
+            .iter()
+            .zip(init_words.iter())
+            .enumerate()
+        {
+            let offset = position * 4;
+            let block_word = u32::from_le_bytes([
+                block[offset],
+                block[offset + 1],
+                block[offset + 2],
+                block[offset + 3]
+            ]);
+            let init_word = u32::from_le_bytes([
+                init_word[0], init_word[1], init_word[2], init_word[3]
+            ]);
+            let word = block_word.wrapping_sub(init_word);
+            expected_data[index * 4..index * 4 + 4]
+                .copy_from_slice(&word.to_le_bytes());
+        }
+
+        assert_eq!(hsalsa20(&key, &input), expected_data);
+    }
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "std")]
+
+extern crate rust_salsa20;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use rust_salsa20::io::{DecryptingReader, EncryptingWriter};
+
+mod common;
+use common::{ciphertext, plaintext, salsa20};
+
+// accepts at most `limit` bytes per call and fails every third call
+struct TrickleWriter {
+    data: Vec<u8>,
+    limit: usize,
+    calls: usize
+}
+
+impl Write for TrickleWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.calls += 1;
+        if self.calls.is_multiple_of(3) {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+
+        let len = buf.len().min(self.limit);
+        self.data.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// returns at most `limit` bytes per call
+struct TrickleReader<R: Read> {
+    inner: R,
+    limit: usize
+}
+
+impl<R: Read> Read for TrickleReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.limit);
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn encrypting_writer_test() {
+    let plaintext = plaintext(10000);
+    let mut writer = EncryptingWriter::new(salsa20(), Vec::new());
+    for chunk in [1, 63, 2, 700, 4096, 5000, 138].iter().scan(0, |offset, &len| {
+        let range = *offset..*offset + len;
+        *offset += len;
+        Some(range)
+    }) {
+        writer.write_all(&plaintext[chunk]).unwrap();
+    }
+    writer.flush().unwrap();
+
+    assert_eq!(writer.get_ref().len(), 10000);
+    assert_eq!(writer.into_inner(), ciphertext(&plaintext));
+}
+
+#[test]
+fn encrypting_writer_partial_write_test() {
+    let plaintext = plaintext(3000);
+    for &limit in &[1, 7, 64, 100, 5000] {
+        let inner = TrickleWriter { data: Vec::new(), limit, calls: 0 };
+        let mut writer = EncryptingWriter::new(salsa20(), inner);
+        writer.write_all(&plaintext[..1234]).unwrap();
+        writer.write_all(&plaintext[1234..]).unwrap();
+        assert_eq!(writer.into_inner().data, ciphertext(&plaintext));
+    }
+}
+
+#[test]
+fn decrypting_reader_test() {
+    let plaintext = plaintext(10000);
+    let ciphertext = ciphertext(&plaintext);
+    for &limit in &[1, 13, 64, 4096, 20000] {
+        let inner = TrickleReader { inner: &ciphertext[..], limit };
+        let mut reader = DecryptingReader::new(salsa20(), inner);
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+    }
+}
+
+#[test]
+fn decrypting_reader_seek_test() {
+    let plaintext = plaintext(5000);
+    let ciphertext = ciphertext(&plaintext);
+    let mut reader = DecryptingReader::new(salsa20(), Cursor::new(ciphertext));
+
+    for &(pos, offset) in &[
+        (SeekFrom::Start(100), 100),
+        (SeekFrom::Current(-37), 63),
+        (SeekFrom::End(-1000), 4000),
+        (SeekFrom::Start(0), 0),
+        (SeekFrom::Current(4095), 4095)
+    ] {
+        assert_eq!(reader.seek(pos).unwrap(), offset as u64);
+        let mut buffer = [0; 200];
+        let len = reader.read(&mut buffer).unwrap();
+        assert_eq!(buffer[..len], plaintext[offset..offset + len]);
+        reader.seek(SeekFrom::Current(-(len as i64))).unwrap();
+    }
+
+    assert!(reader.seek(SeekFrom::Current(-5000)).is_err());
+    let mut buffer = [0; 10];
+    reader.read_exact(&mut buffer).unwrap();
+    assert_eq!(buffer[..], plaintext[4095..4105]);
+}
+
+#[test]
+fn decrypting_reader_start_test() {
+    let plaintext = plaintext(1000);
+    let ciphertext = ciphertext(&plaintext);
+
+    let mut salsa20 = salsa20();
+    salsa20.seek(300);
+    let inner = Cursor::new(&ciphertext[300..]);
+    let mut reader = DecryptingReader::new(salsa20, inner);
+
+    reader.seek(SeekFrom::Start(10)).unwrap();
+    let mut buffer = [0; 100];
+    reader.read_exact(&mut buffer).unwrap();
+    assert_eq!(buffer[..], plaintext[310..410]);
+}
+
+#[test]
+fn encrypting_writer_end_of_sequence_test() {
+    let plaintext = plaintext(10);
+    let mut salsa20 = salsa20();
+    salsa20.seek((1 << 70) - 5);
+    let mut expected = plaintext.clone();
+    salsa20.encrypt(&mut expected);
+
+    salsa20.seek((1 << 70) - 5);
+    let inner = TrickleWriter { data: Vec::new(), limit: 5, calls: 0 };
+    let mut writer = EncryptingWriter::new(salsa20, inner);
+    writer.write_all(&plaintext).unwrap();
+    assert_eq!(writer.into_inner().data, expected);
+}
+
+#[test]
+fn decrypting_reader_advanced_inner_test() {
+    let plaintext = plaintext(1000);
+    let ciphertext = ciphertext(&plaintext);
+
+    let mut salsa20 = salsa20();
+    salsa20.seek(300);
+    let mut inner = Cursor::new(&ciphertext[..]);
+    inner.set_position(300);
+    let mut reader = DecryptingReader::new(salsa20, inner);
+
+    let mut buffer = [0; 50];
+    reader.read_exact(&mut buffer).unwrap();
+    assert_eq!(buffer[..], plaintext[300..350]);
+
+    for &offset in &[310, 100, 0, 950] {
+        reader.seek(SeekFrom::Start(offset as u64)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(buffer[..], plaintext[offset..offset + 50]);
+    }
+}
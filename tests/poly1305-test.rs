@@ -0,0 +1,168 @@
+extern crate rust_salsa20;
+use rust_salsa20::poly1305::{poly1305, verify, Poly1305, VerifyError};
+
+const IETF_TEXT: &[u8] = b"Any submission to the IETF intended by the \
+    Contributor for publication as all or part of an IETF Internet-Draft or \
+    RFC and any statement made within the context of an IETF activity is \
+    considered an \"IETF Contribution\". Such statements include oral \
+    statements in IETF sessions, as well as written and electronic \
+    communications made at any time or place, which are addressed to";
+
+// RFC 8439, section 2.5.2
+#[test]
+fn rfc8439_test() {
+    test(
+        "85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b",
+        b"Cryptographic Forum Research Group",
+        "a8061dc1305136c6c22b8baf0c0127a9"
+    );
+}
+
+// RFC 8439, appendix A.3
+#[test]
+fn rfc8439_appendix_test() {
+    test(
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        &[0; 64],
+        "00000000000000000000000000000000"
+    );
+    test(
+        "0000000000000000000000000000000036e5f6b5c5e06070f0efca96227a863e",
+        IETF_TEXT,
+        "36e5f6b5c5e06070f0efca96227a863e"
+    );
+    test(
+        "36e5f6b5c5e06070f0efca96227a863e00000000000000000000000000000000",
+        IETF_TEXT,
+        "f3477e7cd95417af89a6b8794c310cf0"
+    );
+    test(
+        "1c9240a5eb55d38af333888604f6b5f0473917c1402b80099dca5cbc207075c0",
+        b"'Twas brillig, and the slithy toves\nDid gyre and gimble in the \
+          wabe:\nAll mimsy were the borogoves,\nAnd the mome raths outgrabe.",
+        "4541669a7eaaee61e708dc7cbcc5eb62"
+    );
+    test(
+        "0200000000000000000000000000000000000000000000000000000000000000",
+        &decode("ffffffffffffffffffffffffffffffff"),
+        "03000000000000000000000000000000"
+    );
+    test(
+        "02000000000000000000000000000000ffffffffffffffffffffffffffffffff",
+        &decode("02000000000000000000000000000000"),
+        "03000000000000000000000000000000"
+    );
+    test(
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        &decode(
+            "ffffffffffffffffffffffffffffffff\
+             f0ffffffffffffffffffffffffffffff\
+             11000000000000000000000000000000"
+        ),
+        "05000000000000000000000000000000"
+    );
+    test(
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        &decode(
+            "ffffffffffffffffffffffffffffffff\
+             fbfefefefefefefefefefefefefefefe\
+             01010101010101010101010101010101"
+        ),
+        "00000000000000000000000000000000"
+    );
+    test(
+        "0200000000000000000000000000000000000000000000000000000000000000",
+        &decode("fdffffffffffffffffffffffffffffff"),
+        "faffffffffffffffffffffffffffffff"
+    );
+    test(
+        "0100000000000000040000000000000000000000000000000000000000000000",
+        &decode(
+            "e33594d7505e43b900000000000000003394d7505e4379cd0100000000000000\
+             0000000000000000000000000000000001000000000000000000000000000000"
+        ),
+        "14000000000000005500000000000000"
+    );
+    test(
+        "0100000000000000040000000000000000000000000000000000000000000000",
+        &decode(
+            "e33594d7505e43b900000000000000003394d7505e4379cd0100000000000000\
+             00000000000000000000000000000000"
+        ),
+        "13000000000000000000000000000000"
+    );
+}
+
+// NaCl tests/onetimeauth.c
+#[test]
+fn nacl_test() {
+    test(
+        "eea6a7251c1e72916d11c2cb214d3c252539121d8e234e652d651fa4c8cff880",
+        &decode(
+            "8e993b9f48681273c29650ba32fc76ce48332ea7164d96a4476fb8c531a1186a\
+             c0dfc17c98dce87b4da7f011ec48c97271d2c20f9b928fe2270d6fb863d51738\
+             b48eeee314a7cc8ab932164548e526ae90224368517acfeabd6bb3732bc0e9da\
+             99832b61ca01b6de56244a9e88d5f9b37973f622a43d14a6599b1f654cb45a74\
+             e355a5"
+        ),
+        "f3ffc7703f9400e52a7dfb4b3d3305d9"
+    );
+}
+
+#[test]
+fn verify_test() {
+    let key = [7; 32];
+    let tag = poly1305(&key, IETF_TEXT);
+    assert_eq!(verify(&key, IETF_TEXT, &tag), Ok(()));
+
+    for index in 0..16 {
+        let mut forged_tag = tag;
+        forged_tag[index] ^= 1;
+        assert_eq!(verify(&key, IETF_TEXT, &forged_tag), Err(VerifyError));
+    }
+    assert_eq!(verify(&key, &IETF_TEXT[1..], &tag), Err(VerifyError));
+
+    let mut poly1305 = Poly1305::new(&key);
+    poly1305.update(IETF_TEXT);
+    assert_eq!(poly1305.verify(&tag), Ok(()));
+}
+
+#[test]
+fn update_test() {
+    let key = [
+        0x85, 0xd6, 0xbe, 0x78, 0x57, 0x55, 0x6d, 0x33, 0x7f, 0x44, 0x52, 0xfe,
+        0x42, 0xd5, 0x06, 0xa8, 0x01, 0x03, 0x80, 0x8a, 0xfb, 0x0d, 0xb2, 0xfd,
+        0x4a, 0xbf, 0xf6, 0xaf, 0x41, 0x49, 0xf5, 0x1b
+    ];
+    let expected_data = poly1305(&key, IETF_TEXT);
+
+    for &step in &[1, 3, 15, 16, 17, 33, 64, 100] {
+        let mut poly1305 = Poly1305::new(&key);
+        IETF_TEXT.chunks(step).for_each(|chunk| poly1305.update(chunk));
+        assert_eq!(poly1305.finalize(), expected_data);
+    }
+
+    let mut poly1305 = Poly1305::new(&key);
+    poly1305.update(&IETF_TEXT[..7]);
+    poly1305.update(&[]);
+    poly1305.update(&IETF_TEXT[7..300]);
+    poly1305.update(&IETF_TEXT[300..]);
+    assert_eq!(poly1305.finalize(), expected_data);
+}
+
+fn test(key: &str, message: &[u8], expected_data: &str) {
+    let mut key_bytes = [0; 32];
+    key_bytes.copy_from_slice(&decode(key));
+    let mut expected_tag = [0; 16];
+    expected_tag.copy_from_slice(&decode(expected_data));
+
+    assert_eq!(poly1305(&key_bytes, message), expected_tag);
+    assert_eq!(verify(&key_bytes, message, &expected_tag), Ok(()));
+}
+
+fn decode(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+        .collect()
+}
@@ -0,0 +1,100 @@
+extern crate rust_salsa20;
+use rust_salsa20::{Salsa, Salsa20_8, Salsa20_12, Salsa20_20};
+use rust_salsa20::{Rounds, R8, R12, R20};
+use rust_salsa20::Key::{Key16, Key32};
+
+// ECRYPT verified test vectors, set 1, vector 0 (256-bit key)
+#[test]
+fn salsa20_8_test() {
+    test::<R8>([
+        "B1F599E9B0D96DF436AE31F5EF589565B92D245DB5A1D4C7A78E5E8D0146F8A4\
+         9D326C1A3BF50C052C9C8F114DC74972C4469591E31C9ED11927AA9871F38583",
+        "0C427AD0D68D752517649EC6D311FE7CD71DBFF6E6217B91A83D45F33F5E5224\
+         BDF86D09A132884B152105842B5BDCA86A0B1ADC568F5C04B4D021A2AD3E0E26",
+        "F6E86C2E6F768A167E484A4116A63322FCE370FE40623A13856550E6A3452478\
+         D8257E4B7322D3B98CD8505E21C54A31D78F9444366C72AC9B31FB747F7F2592",
+        "53BF865C66A344CFCD19177476A05ACA5851CC45224B196ABF3206D899E7FE3B\
+         13B3F028FA849B5564561A9181EA69E512BC34DA29180CDF6811E40A9A06A8D1"
+    ]);
+}
+
+#[test]
+fn salsa20_12_test() {
+    test::<R12>([
+        "AFE411ED1C4E07E4D0CDE3B33E31EC190FA4CC796A58BAFB848EAD8D07D02CD2\
+         D4B6F9F30CB0B57007E3733895CC8D1060107975ACAEEB689B6CF614AB64A3D6",
+        "8966E93E875E8065AC6F3A1A3E2146F83D5EA93CA987FF9F13ED6ADE169665AE\
+         3527FCA5613AF081C0E773DA6E7C74C5642ECAC53FEBF15A699AC2C8255CC100",
+        "C89DB39DD8872492ABF8109462B3639BB18C64ED500B70D2836B6194D11A77AC\
+         8C14DD8E1DF0B3924DDA24563E2719E2635C61F63B9AE60D56D5F3512851B4B1",
+        "87A5191EC2E3C9049FA524CD8673E0677C77ADCF8AB5328FD828C4ACB3ECCCA5\
+         49ADEDA04872518ECDF874ADCB2420C7BD1CCFE561B074080224FA7176F0CB5F"
+    ]);
+}
+
+#[test]
+fn salsa20_20_test() {
+    test::<R20>([
+        "E3BE8FDD8BECA2E3EA8EF9475B29A6E7003951E1097A5C38D23B7A5FAD9F6844\
+         B22C97559E2723C7CBBD3FE4FC8D9A0744652A83E72A9C461876AF4D7EF1A117",
+        "57BE81F47B17D9AE7C4FF15429A73E10ACF250ED3A90A93C711308A74C6216A9\
+         ED84CD126DA7F28E8ABF8BB63517E1CA98E712F4FB2E1A6AED9FDC73291FAA17",
+        "958211C4BA2EBD5838C635EDB81F513A91A294E194F1C039AEEC657DCE40AA7E\
+         7C0AF57CACEFA40C9F14B71A4B3456A63E162EC7D8D10B8FFB1810D71001B618",
+        "696AFCFD0CDDCC83C7E77F11A649D79ACDC3354E9635FF137E929933A0BD6F53\
+         77EFA105A3A4266B7C0D089D08F1E855CC32B15B93784A36E56A76CC64BC8477"
+    ]);
+}
+
+// ECRYPT verified test vectors, set 1, vector 0 (128-bit key)
+#[test]
+fn salsa20_20_key16_test() {
+    let mut key = [0; 16];
+    key[0] = 0x80;
+    let mut salsa = Salsa20_20::new(Key16(key), [0; 8], 0);
+    let mut buffer = [0; 64];
+    salsa.generate(&mut buffer);
+
+    assert_eq!(buffer[..], decode(
+        "4DFA5E481DA23EA09A31022050859936DA52FCEE218005164F267CB65F5CFD7F\
+         2B4F97E0FF16924A52DF269515110A07F9E460BC65EF95DA58F740B7D1DBB0AA"
+    )[..]);
+}
+
+#[test]
+fn rounds_differ_test() {
+    let key = Key32([7; 32]);
+    let mut buffers = [[0; 64]; 3];
+    Salsa20_8::new(key, [1; 8], 0).generate(&mut buffers[0]);
+    Salsa20_12::new(key, [1; 8], 0).generate(&mut buffers[1]);
+    Salsa20_20::new(key, [1; 8], 0).generate(&mut buffers[2]);
+
+    assert_ne!(buffers[0][..], buffers[1][..]);
+    assert_ne!(buffers[1][..], buffers[2][..]);
+    assert_ne!(buffers[0][..], buffers[2][..]);
+}
+
+fn test<R: Rounds>(expected_data: [&str; 4]) {
+    let mut key = [0; 32];
+    key[0] = 0x80;
+    let mut salsa = Salsa::<R>::new(Key32(key), [0; 8], 0);
+    let mut buffer = [0; 512];
+    salsa.generate(&mut buffer[..100]);
+    salsa.generate(&mut buffer[100..]);
+
+    let ranges = [0..64, 192..256, 256..320, 448..512];
+    for (range, expected_data) in ranges.iter().zip(expected_data.iter()) {
+        assert_eq!(buffer[range.clone()], decode(expected_data)[..]);
+    }
+
+    salsa.set_counter(3);
+    salsa.generate(&mut buffer[..64]);
+    assert_eq!(buffer[..64], decode(expected_data[1])[..]);
+}
+
+fn decode(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+        .collect()
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "rand_core")]
+
+extern crate rust_salsa20;
+use rust_salsa20::rand_core::{CryptoRng, RngCore, SeedableRng};
+use rust_salsa20::{Salsa20, Salsa20Rng, Key::Key32};
+
+const SEED: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+
+fn keystream(stream: u64, len: usize) -> Vec<u8> {
+    let mut buffer = vec![0; len];
+    Salsa20::new(Key32(SEED), stream.to_le_bytes(), 0).generate(&mut buffer);
+    buffer
+}
+
+fn word(keystream: &[u8], index: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&keystream[index * 4..index * 4 + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+#[test]
+fn next_u32_test() {
+    let keystream = keystream(0, 1024);
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    for index in 0..256 {
+        assert_eq!(rng.next_u32(), word(&keystream, index));
+    }
+    assert_eq!(rng.get_word_pos(), 256);
+}
+
+#[test]
+fn next_u64_test() {
+    let keystream = keystream(0, 256);
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    assert_eq!(rng.next_u32(), word(&keystream, 0));
+    for index in 0..30 {
+        let low = u64::from(word(&keystream, index * 2 + 1));
+        let high = u64::from(word(&keystream, index * 2 + 2));
+        assert_eq!(rng.next_u64(), low | high << 32);
+    }
+}
+
+#[test]
+fn fill_bytes_test() {
+    let keystream = keystream(0, 2048);
+
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    let mut buffer = [0; 1000];
+    rng.fill_bytes(&mut buffer);
+    assert_eq!(buffer[..], keystream[..1000]);
+    assert_eq!(rng.get_word_pos(), 250);
+
+    // a partly used word is dropped
+    let mut buffer = [0; 3];
+    rng.fill_bytes(&mut buffer);
+    assert_eq!(buffer[..], keystream[1000..1003]);
+    assert_eq!(rng.next_u32(), word(&keystream, 251));
+
+    let mut buffer = [0; 700];
+    rng.try_fill_bytes(&mut buffer).unwrap();
+    assert_eq!(buffer[..], keystream[1008..1708]);
+}
+
+#[test]
+fn word_pos_test() {
+    let keystream = keystream(0, 8192);
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    for &position in &[0, 1, 15, 16, 17, 100, 512, 1023] {
+        rng.set_word_pos(position);
+        assert_eq!(rng.get_word_pos(), position);
+
+        let position = position as usize;
+        assert_eq!(rng.next_u32(), word(&keystream, position));
+        assert_eq!(rng.next_u32(), word(&keystream, position + 1));
+
+        let mut buffer = [0; 100];
+        rng.fill_bytes(&mut buffer);
+        let offset = (position + 2) * 4;
+        assert_eq!(buffer[..], keystream[offset..offset + 100]);
+    }
+}
+
+#[test]
+fn word_pos_end_test() {
+    let mut buffer = [0; 64];
+    Salsa20::new(Key32(SEED), [0; 8], u64::MAX).generate(&mut buffer);
+
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    rng.set_word_pos((1 << 68) - 1);
+    assert_eq!(rng.get_word_pos(), (1 << 68) - 1);
+    assert_eq!(rng.next_u32(), word(&buffer, 15));
+    assert_eq!(rng.get_word_pos(), 0);
+}
+
+#[test]
+fn stream_test() {
+    let keystream = keystream(0x0102030405060708, 256);
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    assert_eq!(rng.get_stream(), 0);
+
+    rng.next_u32();
+    rng.set_stream(0x0102030405060708);
+    assert_eq!(rng.get_stream(), 0x0102030405060708);
+    assert_eq!(rng.get_word_pos(), 1);
+    assert_eq!(rng.next_u32(), word(&keystream, 1));
+
+    let mut other_rng = Salsa20Rng::from_seed(SEED);
+    other_rng.set_stream(0x0102030405060708);
+    other_rng.set_word_pos(2);
+    assert_eq!(rng.next_u64(), other_rng.next_u64());
+}
+
+#[test]
+fn reproduce_test() {
+    let mut rng = Salsa20Rng::seed_from_u64(42);
+    rng.set_stream(7);
+    rng.set_word_pos(12345);
+    let mut expected_data = [0; 300];
+    rng.fill_bytes(&mut expected_data);
+
+    let mut clone = rng.clone();
+    assert_eq!(clone.next_u64(), rng.next_u64());
+
+    let mut rng = Salsa20Rng::seed_from_u64(42);
+    rng.set_word_pos(12345);
+    rng.set_stream(7);
+    let mut buffer = [0; 300];
+    rng.fill_bytes(&mut buffer);
+    assert_eq!(buffer[..], expected_data[..]);
+
+    // a position past the low word of the counter
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    rng.set_word_pos(16 << 32);
+    rng.set_stream(7);
+    let mut other_rng = Salsa20Rng::from_seed(SEED);
+    other_rng.set_stream(7);
+    other_rng.set_word_pos(16 << 32);
+    assert_eq!(rng.next_u64(), other_rng.next_u64());
+}
+
+#[test]
+fn crypto_rng_test() {
+    fn test<R: RngCore + CryptoRng>(rng: &mut R) -> u32 {
+        rng.next_u32()
+    }
+
+    let keystream = keystream(0, 4);
+    assert_eq!(
+        test(&mut Salsa20Rng::from_seed(SEED)),
+        word(&keystream, 0)
+    );
+}
+
+#[test]
+fn debug_test() {
+    let mut rng = Salsa20Rng::from_seed(SEED);
+    rng.set_stream(3);
+    rng.next_u32();
+    assert_eq!(
+        format!("{:?}", rng),
+        "Salsa20Rng { word_pos: 1, stream: 3, .. }"
+    );
+}
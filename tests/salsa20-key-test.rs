@@ -0,0 +1,92 @@
+extern crate rust_salsa20;
+use rust_salsa20::{Salsa20, Salsa20Key, Salsa20_8, SalsaKey, R8};
+use rust_salsa20::Key::{Key16, Key32};
+
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+#[test]
+fn stream_test() {
+    for &key in [Key16([1; 16]), Key32([2; 32])].iter() {
+        let salsa20_key = Salsa20Key::new(key);
+        for &counter in [0, 1, 0xffffffff, u64::MAX].iter() {
+            let mut expected_data = [0; 100];
+            Salsa20::new(key, NONCE, counter).generate(&mut expected_data);
+
+            let mut buffer = [0; 100];
+            salsa20_key.stream(NONCE, counter).generate(&mut buffer);
+            assert_eq!(buffer[..], expected_data[..]);
+        }
+    }
+
+    let mut expected_data = [0; 100];
+    Salsa20_8::new(Key32([3; 32]), NONCE, 5).generate(&mut expected_data);
+    let mut buffer = [0; 100];
+    SalsaKey::<R8>::new(Key32([3; 32]))
+        .stream(NONCE, 5)
+        .generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[..]);
+}
+
+#[test]
+fn set_nonce_test() {
+    let key = Key32([4; 32]);
+    test(key, 0);
+    test(key, 10);
+    test(key, 64);
+    test(key, 100);
+    test(key, (1 << 70) - 1);
+
+    fn test(key: rust_salsa20::Key, position: u128) {
+        let mut expected_data = [0; 100];
+        let mut salsa20 = Salsa20::new(key, NONCE, 0);
+        salsa20.seek(position);
+        salsa20.generate(&mut expected_data);
+
+        let mut salsa20 = Salsa20::new(key, [0; 8], 0);
+        salsa20.seek(position);
+        let mut buffer = [0; 100];
+        salsa20.set_nonce(NONCE);
+        assert_eq!(salsa20.current_pos(), position);
+        salsa20.generate(&mut buffer);
+        assert_eq!(buffer[..], expected_data[..]);
+    }
+}
+
+#[test]
+fn set_nonce_high_counter_test() {
+    let key = Key32([4; 32]);
+    let mut expected_data = [0; 100];
+    Salsa20::new(key, NONCE, 1 << 32).generate(&mut expected_data);
+
+    // the high word of the counter changes before the nonce, at the start
+    // of a block
+    let mut salsa20 = Salsa20::new(key, [0; 8], 0);
+    salsa20.set_counter(1 << 32);
+    salsa20.set_nonce(NONCE);
+    let mut buffer = [0; 100];
+    salsa20.generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[..]);
+}
+
+#[test]
+fn set_nonce_messages_test() {
+    let key = Key16([5; 16]);
+    let mut salsa20 = Salsa20Key::new(key).stream([0; 8], 0);
+
+    for nonce in 0..4u64 {
+        let mut expected_data = [7; 33];
+        Salsa20::new(key, nonce.to_le_bytes(), 0).encrypt(&mut expected_data);
+
+        salsa20.set_nonce(nonce.to_le_bytes());
+        salsa20.set_counter(0);
+        let mut buffer = [7; 33];
+        salsa20.encrypt(&mut buffer);
+        assert_eq!(buffer, expected_data);
+    }
+}
+
+#[test]
+fn debug_test() {
+    let key = Salsa20Key::new(Key32([6; 32]));
+    assert_eq!(format!("{:?}", key), "SalsaKey { .. }");
+}
@@ -0,0 +1,119 @@
+extern crate rust_salsa20;
+use rust_salsa20::{Salsa20, XSalsa20, Key::Key32};
+
+const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+#[test]
+fn seek_test() {
+    let mut expected_data = vec![0; 4096];
+    Salsa20::new(Key32(KEY), NONCE, 0).generate(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    let mut rng = XorShift(0x2545f4914f6cdd1d);
+    for _ in 0..1000 {
+        let position = rng.next(expected_data.len());
+        let len = rng.next(expected_data.len() - position + 1);
+
+        salsa20.seek(position as u128);
+        assert_eq!(salsa20.current_pos(), position as u128);
+
+        let mut buffer = vec![0; len];
+        salsa20.generate(&mut buffer);
+        assert_eq!(buffer[..], expected_data[position..position + len]);
+        assert_eq!(salsa20.current_pos(), (position + len) as u128);
+    }
+}
+
+#[test]
+fn seek_encrypt_test() {
+    let data = vec![7; 1000];
+    let mut expected_data = data.clone();
+    Salsa20::new(Key32(KEY), NONCE, 0).encrypt(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    let mut rng = XorShift(0x9e3779b97f4a7c15);
+    for _ in 0..100 {
+        let position = rng.next(data.len());
+        let mut buffer = data[position..].to_vec();
+
+        salsa20.seek(position as u128);
+        salsa20.encrypt(&mut buffer);
+        assert_eq!(buffer[..], expected_data[position..]);
+    }
+}
+
+#[test]
+fn seek_large_position_test() {
+    let mut expected_data = [0; 128];
+    Salsa20::new(Key32(KEY), NONCE, 0xffffffff).generate(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    salsa20.seek((0xffffffff << 6) + 10);
+    assert_eq!(salsa20.current_pos(), (0xffffffff << 6) + 10);
+
+    let mut buffer = [0; 100];
+    salsa20.generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[10..110]);
+}
+
+#[test]
+fn seek_last_block_test() {
+    let mut expected_data = [0; 64];
+    Salsa20::new(Key32(KEY), NONCE, u64::MAX).generate(&mut expected_data);
+
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 0);
+    salsa20.seek((1 << 70) - 1);
+    assert_eq!(salsa20.current_pos(), (1 << 70) - 1);
+
+    let mut buffer = [0; 1];
+    salsa20.generate(&mut buffer);
+    assert_eq!(buffer[0], expected_data[63]);
+}
+
+#[test]
+#[should_panic]
+fn seek_out_of_range_test() {
+    Salsa20::new(Key32(KEY), NONCE, 0).seek(1 << 70);
+}
+
+#[test]
+fn set_counter_current_pos_test() {
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, 5);
+    assert_eq!(salsa20.current_pos(), 320);
+
+    salsa20.encrypt(&mut [0; 3]);
+    assert_eq!(salsa20.current_pos(), 323);
+
+    salsa20.set_counter(1);
+    assert_eq!(salsa20.current_pos(), 64);
+}
+
+#[test]
+fn xsalsa20_seek_test() {
+    let nonce = [3; 24];
+    let mut expected_data = [0; 256];
+    XSalsa20::new(Key32(KEY), nonce, 0).generate(&mut expected_data);
+
+    let mut xsalsa20 = XSalsa20::new(Key32(KEY), nonce, 0);
+    xsalsa20.seek(100);
+    assert_eq!(xsalsa20.current_pos(), 100);
+
+    let mut buffer = [0; 100];
+    xsalsa20.generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[100..200]);
+}
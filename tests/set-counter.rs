@@ -1,5 +1,39 @@
 extern crate rust_salsa20;
-use rust_salsa20::{Salsa20, Key::Key32};
+use rust_salsa20::{Salsa20, Salsa20_8, Key::Key32};
+
+const KEY: [u8; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+];
+const NONCE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // mixes full-range counters with ones near the 2^32 and 2^64 borders
+    fn next_counter(&mut self) -> u64 {
+        let value = self.next();
+        match value % 4 {
+            0 => value,
+            1 => value >> 40,
+            2 => (1 << 32) + (value >> 60) - 8,
+            _ => u64::MAX - (value >> 61)
+        }
+    }
+}
+
+fn block(counter: u64) -> [u8; 128] {
+    let mut buffer = [0; 128];
+    Salsa20::new(Key32(KEY), NONCE, counter).generate(&mut buffer);
+    buffer
+}
 
 #[test]
 fn generate_test() {
@@ -30,12 +64,7 @@ fn generate_test() {
     );
 
     fn test(counter: u64, expected_data: Vec<u8>) {
-        let key = Key32([
-            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
-            17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
-        ]);
-        let nonce = [1, 2, 3, 4, 5, 6, 7, 8];
-        let mut salsa = Salsa20::new(key, nonce, 0);
+        let mut salsa = Salsa20::new(Key32(KEY), NONCE, 0);
         let mut buffer = vec![0; 100];
         salsa.generate(&mut buffer);
 
@@ -45,3 +74,67 @@ fn generate_test() {
         assert_eq!(buffer, expected_data);
     }
 }
+
+#[test]
+fn set_counter_test() {
+    let mut rng = XorShift(0x2545f4914f6cdd1d);
+    for _ in 0..2000 {
+        let first_counter = rng.next_counter();
+        let second_counter = rng.next_counter();
+
+        let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, first_counter);
+        let mut buffer = [0; 128];
+        salsa20.generate(&mut buffer[..rng.next() as usize % 128]);
+
+        salsa20.set_counter(second_counter);
+        salsa20.generate(&mut buffer);
+        assert_eq!(
+            buffer[..],
+            block(second_counter)[..],
+            "{} -> {}",
+            first_counter,
+            second_counter
+        );
+    }
+}
+
+#[test]
+fn set_counter_below_high_word_test() {
+    let mut salsa20 = Salsa20::new(Key32(KEY), NONCE, (1 << 32) + 5);
+    salsa20.generate(&mut [0; 64]);
+
+    for &counter in &[5, 0, 0xffffffff, 1 << 32, 0, u64::MAX, 7] {
+        let mut buffer = [0; 128];
+        salsa20.set_counter(counter);
+        salsa20.generate(&mut buffer);
+        assert_eq!(buffer[..], block(counter)[..], "counter {}", counter);
+    }
+}
+
+#[test]
+fn counter_carry_test() {
+    for &counter in &[0xffffffff, (2 << 32) - 1, u64::MAX] {
+        let mut buffer = [0; 128];
+        Salsa20::new(Key32(KEY), NONCE, counter).generate(&mut buffer);
+        assert_eq!(buffer[64..], block(counter.wrapping_add(1))[..64]);
+    }
+}
+
+#[test]
+fn reduced_rounds_set_counter_test() {
+    let mut rng = XorShift(0x9e3779b97f4a7c15);
+    for _ in 0..200 {
+        let first_counter = rng.next_counter();
+        let second_counter = rng.next_counter();
+
+        let mut expected_data = [0; 64];
+        Salsa20_8::new(Key32(KEY), NONCE, second_counter)
+            .generate(&mut expected_data);
+
+        let mut buffer = [0; 64];
+        let mut salsa20 = Salsa20_8::new(Key32(KEY), NONCE, first_counter);
+        salsa20.set_counter(second_counter);
+        salsa20.generate(&mut buffer);
+        assert_eq!(buffer, expected_data);
+    }
+}
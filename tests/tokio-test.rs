@@ -0,0 +1,74 @@
+#![cfg(feature = "tokio")]
+
+extern crate rust_salsa20;
+use tokio::io::{duplex, AsyncRead, AsyncReadExt, AsyncWriteExt};
+use rust_salsa20::io::tokio::{DecryptingReader, EncryptingWriter};
+
+mod common;
+use common::{ciphertext, plaintext, salsa20, FlakyWriter};
+
+async fn read_all<R: AsyncRead + Unpin>(reader: &mut R) -> Vec<u8> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).await.unwrap();
+    data
+}
+
+#[tokio::test]
+async fn duplex_test() {
+    let plaintext = plaintext(10000);
+    let (client, server) = duplex(64);
+    let mut writer = EncryptingWriter::new(salsa20(), client);
+    let mut reader = DecryptingReader::new(salsa20(), server);
+
+    let write = async {
+        writer.write_all(&plaintext[..3333]).await.unwrap();
+        writer.write_all(&plaintext[3333..]).await.unwrap();
+        writer.shutdown().await.unwrap();
+    };
+    let (_, data) = tokio::join!(write, read_all(&mut reader));
+    assert_eq!(data, plaintext);
+}
+
+#[tokio::test]
+async fn duplex_ciphertext_test() {
+    let plaintext = plaintext(5000);
+    let (client, mut server) = duplex(100);
+    let mut writer = EncryptingWriter::new(salsa20(), client);
+
+    let write = async {
+        for chunk in plaintext.chunks(77) {
+            writer.write_all(chunk).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+        writer.shutdown().await.unwrap();
+    };
+    let (_, data) = tokio::join!(write, read_all(&mut server));
+    assert_eq!(data, ciphertext(&plaintext));
+}
+
+#[tokio::test]
+async fn partial_write_test() {
+    let plaintext = plaintext(1000);
+    let inner = FlakyWriter::new();
+    let mut writer = EncryptingWriter::new(salsa20(), inner);
+    writer.write_all(&plaintext).await.unwrap();
+    assert_eq!(writer.into_inner().data, ciphertext(&plaintext));
+}
+
+#[tokio::test]
+async fn small_reads_test() {
+    let plaintext = plaintext(1000);
+    let ciphertext = ciphertext(&plaintext);
+    let mut reader = DecryptingReader::new(salsa20(), &ciphertext[..]);
+
+    let mut data = Vec::new();
+    let mut buffer = [0; 13];
+    loop {
+        let len = reader.read(&mut buffer).await.unwrap();
+        if len == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..len]);
+    }
+    assert_eq!(data, plaintext);
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "zeroize")]
+
+extern crate rust_salsa20;
+use rust_salsa20::poly1305::Poly1305;
+use rust_salsa20::zeroize::{Zeroize, ZeroizeOnDrop};
+use rust_salsa20::{ChaCha20, ChaCha20Ietf, Key, Salsa20, Salsa20_8};
+use rust_salsa20::{Secret, XChaCha20, XSalsa20};
+
+fn zeroize_on_drop<T: ZeroizeOnDrop + Clone>() {}
+
+fn copy<T: Copy>() {}
+
+#[test]
+fn zeroize_on_drop_test() {
+    zeroize_on_drop::<Secret<Salsa20>>();
+    zeroize_on_drop::<Secret<Salsa20_8>>();
+    zeroize_on_drop::<Secret<XSalsa20>>();
+    zeroize_on_drop::<Secret<ChaCha20>>();
+    zeroize_on_drop::<Secret<ChaCha20Ietf>>();
+    zeroize_on_drop::<Secret<XChaCha20>>();
+    zeroize_on_drop::<Secret<Poly1305>>();
+}
+
+// the feature only adds `Secret`, the cipher types stay `Copy`
+#[test]
+fn copy_test() {
+    copy::<Salsa20>();
+    copy::<Salsa20_8>();
+    copy::<XSalsa20>();
+    copy::<ChaCha20>();
+    copy::<ChaCha20Ietf>();
+    copy::<XChaCha20>();
+    copy::<Poly1305>();
+}
+
+#[test]
+fn key_zeroize_test() {
+    let mut key = Key::Key32([1; 32]);
+    key.zeroize();
+    match key {
+        Key::Key32(key) => assert_eq!(key, [0; 32]),
+        Key::Key16(_) => unreachable!()
+    }
+
+    let mut key = Key::Key16([1; 16]);
+    key.zeroize();
+    match key {
+        Key::Key16(key) => assert_eq!(key, [0; 16]),
+        Key::Key32(_) => unreachable!()
+    }
+}
+
+#[test]
+fn secret_test() {
+    let key = Key::Key32([1; 32]);
+    let mut expected_data = [0; 100];
+    XSalsa20::new(key, [2; 24], 0).generate(&mut expected_data);
+
+    let mut xsalsa20 = Secret::new(XSalsa20::new(key, [2; 24], 0));
+    let mut buffer = [0; 100];
+    xsalsa20.generate(&mut buffer);
+    assert_eq!(buffer[..], expected_data[..]);
+
+    let mut expected_tag = Poly1305::new(&[3; 32]);
+    expected_tag.update(b"message");
+    let mut poly1305 = Secret::from(Poly1305::new(&[3; 32]));
+    poly1305.update(b"message");
+    assert_eq!(poly1305.finalize(), expected_tag.finalize());
+}
+
+#[test]
+fn clone_test() {
+    let salsa20 = Salsa20::new(Key::Key32([1; 32]), [2; 8], 0);
+    let mut salsa20 = Secret::new(salsa20);
+    salsa20.generate(&mut [0; 10]);
+    let mut clone = salsa20.clone();
+
+    let mut buffer = [0; 100];
+    let mut clone_buffer = [0; 100];
+    salsa20.generate(&mut buffer);
+    drop(salsa20);
+    clone.generate(&mut clone_buffer);
+    assert_eq!(buffer[..], clone_buffer[..]);
+}
+
+#[test]
+#[cfg(feature = "rand_core")]
+fn rng_zeroize_on_drop_test() {
+    zeroize_on_drop::<rust_salsa20::Salsa20Rng>();
+}